@@ -0,0 +1,65 @@
+use music_timer::{music_time::MusicTime, time_signature::TimeSignature};
+use std::{fs::File, io::Write};
+
+const SAMPLE_RATE: u32 = 44100;
+const CLICK_SAMPLES: usize = 441;
+
+fn main() -> std::io::Result<()> {
+    // The musical math is done by the crate, the audio is up to you
+    let time_signature = TimeSignature::new(4, 4);
+    let bpm = 120.0;
+    let bars = 4;
+    let clicks = music_timer::click_track_positions(&time_signature, bpm, bars);
+
+    // Render a short sine blip for every click, higher on the downbeat
+    let duration = music_timer::duration_between(
+        &MusicTime::new(1, 1, 1),
+        &MusicTime::new(bars + 1, 1, 1),
+        &time_signature,
+        bpm,
+    );
+    let sample_count = (duration.as_secs_f64() * f64::from(SAMPLE_RATE)) as usize;
+    let mut samples = vec![0i16; sample_count];
+    for (offset, is_accented) in clicks {
+        let frequency = if is_accented { 1760.0 } else { 880.0 };
+        let start = (offset.as_secs_f64() * f64::from(SAMPLE_RATE)) as usize;
+        for (i, sample) in samples
+            .iter_mut()
+            .skip(start)
+            .take(CLICK_SAMPLES)
+            .enumerate()
+        {
+            let phase = i as f64 * frequency * 2.0 * std::f64::consts::PI / f64::from(SAMPLE_RATE);
+            let fade = 1.0 - i as f64 / CLICK_SAMPLES as f64;
+            *sample = (phase.sin() * fade * f64::from(i16::MAX) * 0.8) as i16;
+        }
+    }
+
+    // Write a mono 16 bit PCM WAV
+    let data_size = (samples.len() * 2) as u32;
+    let mut file = File::create("click_track.wav")?;
+    file.write_all(b"RIFF")?;
+    file.write_all(&(36 + data_size).to_le_bytes())?;
+    file.write_all(b"WAVEfmt ")?;
+    file.write_all(&16u32.to_le_bytes())?;
+    file.write_all(&1u16.to_le_bytes())?;
+    file.write_all(&1u16.to_le_bytes())?;
+    file.write_all(&SAMPLE_RATE.to_le_bytes())?;
+    file.write_all(&(SAMPLE_RATE * 2).to_le_bytes())?;
+    file.write_all(&2u16.to_le_bytes())?;
+    file.write_all(&16u16.to_le_bytes())?;
+    file.write_all(b"data")?;
+    file.write_all(&data_size.to_le_bytes())?;
+    for sample in samples {
+        file.write_all(&sample.to_le_bytes())?;
+    }
+
+    println!(
+        "Wrote {} bars of {}/{} at {}bpm to click_track.wav",
+        bars,
+        time_signature.get_numerator(),
+        time_signature.get_denominator(),
+        bpm
+    );
+    Ok(())
+}
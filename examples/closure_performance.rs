@@ -0,0 +1,36 @@
+use music_timer::music_time::MusicTime;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+fn main() {
+    use std::thread;
+
+    let performance_end = MusicTime::new(2, 4, 8);
+    let is_playing = Arc::new(AtomicBool::new(true));
+
+    // Closures are handy for small scripts that do not need a `MusicTimerState`
+    let is_playing_state = is_playing.clone();
+    let mut performer = music_timer::create_performance_engine(4, 4, 120.0)
+        .unwrap()
+        .on_beat_interval(move |current_time| {
+            // Check to end the performance
+            is_playing_state.store(*current_time < performance_end, Ordering::SeqCst);
+        })
+        .on_beat(|current_time| {
+            println!(
+                "{:02}.{}.{}",
+                current_time.get_bar(),
+                current_time.get_beat(),
+                current_time.get_beat_interval()
+            );
+        })
+        .on_bar(|current_time| println!("Bar {}!", current_time.get_bar()));
+
+    let sleep_duration = performer.get_beat_interval_duration() / 2;
+    while is_playing.load(Ordering::SeqCst) {
+        performer.pulse_closures();
+        thread::sleep(sleep_duration);
+    }
+}
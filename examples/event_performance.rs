@@ -52,7 +52,7 @@ fn main() {
     };
 
     // Run our main loop
-    let mut performer = music_timer::create_performance_engine(3, 4, 155.0);
+    let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
 
     // We can set the delay to be half the trigger target. This will give
     // us a resonable cycle speed with enough buffer to keep an accurate time.
@@ -0,0 +1,93 @@
+#![allow(dead_code)]
+
+//!
+//! Sources of time used by the performance engine.
+//!
+
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// This trait is used by `MusicTimerEngine` to read the flow of time.
+/// Invoke it to drive the engine from a time source other than the system clock.
+pub trait Clock {
+    /// Gets the current time as a duration since the origin of the clock.
+    fn now(&self) -> Duration;
+}
+
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default)]
+/// Clock that reads the system time. This is the default clock of the engine.
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    /// Gets the duration since the unix epoch. This will be zero if the
+    /// system time is before the epoch.
+    fn now(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// Clock that only moves when told to. Handy for driving the engine
+/// deterministically in tests.
+pub struct ManualClock {
+    now: Duration,
+}
+
+impl ManualClock {
+    /// Create a new `ManualClock` starting at zero.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::clock::{Clock, ManualClock};
+    /// let clock = ManualClock::new();
+    /// assert_eq!(clock.now(), std::time::Duration::default());
+    /// ```
+    pub fn new() -> Self {
+        ManualClock::default()
+    }
+
+    /// Advance the clock.
+    ///
+    /// # Arguments
+    /// * `duration` - The duration to move the clock forward by.
+    pub fn advance(&mut self, duration: Duration) -> &mut Self {
+        self.now += duration;
+        self
+    }
+
+    /// Set the time of the clock. The time may be set backwards.
+    ///
+    /// # Arguments
+    /// * `now` - The new time of the clock.
+    pub fn set(&mut self, now: Duration) -> &mut Self {
+        self.now = now;
+        self
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now
+    }
+}
+
+mod tests {
+    #[test]
+    fn test_manual_clock() {
+        use crate::clock::{Clock, ManualClock};
+        use std::time::Duration;
+
+        let mut clock = ManualClock::new();
+        assert_eq!(clock.now(), Duration::default());
+        clock.advance(Duration::from_millis(10));
+        clock.advance(Duration::from_millis(15));
+        assert_eq!(clock.now(), Duration::from_millis(25));
+        clock.set(Duration::from_millis(5));
+        assert_eq!(clock.now(), Duration::from_millis(5));
+    }
+}
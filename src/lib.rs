@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 #![crate_name = "music_timer"]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
 //!
 //! _Music Timer_ is a crate with music time and counting utilities featuring a callback performance engine to help with triggering events in music time. Written in Rust.
@@ -64,7 +65,7 @@
 //!     };
 //!
 //!     // Run our main loop
-//!     let mut performer = music_timer::create_performance_engine(3, 4, 155.0);
+//!     let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
 //!
 //!     // We can set the delay to be half the trigger target. This will give
 //!     // us a reasonable cycle speed with enough buffer to keep an accurate time.
@@ -82,13 +83,35 @@
 //!     }
 //! }
 //! ```
+//!
+//! # `no_std`
+//! Without the default `std` feature the crate only depends on `core`. `MusicTime`,
+//! `TimeSignature`, `MusicTimeCounter`, the `Clock` trait and `ManualClock` remain. The
+//! performance engine, `performance_thread`, `SystemClock`, `Scheduler`, `TapTempo` and
+//! `TempoMap` need `std`.
 
+pub mod clock;
 pub mod music_time;
 pub mod music_time_counter;
+#[cfg(feature = "std")]
 pub mod music_timer_engine;
+#[cfg(feature = "std")]
+pub mod performance_thread;
+#[cfg(feature = "tokio")]
+pub mod runner;
+#[cfg(feature = "std")]
+pub mod scheduler;
+#[cfg(feature = "std")]
+pub mod tap_tempo;
+#[cfg(feature = "std")]
+pub mod tempo_map;
 pub mod time_signature;
 
-/// Creates a new music timer performance engine.
+/// Creates a new music timer performance engine. An error is returned for an invalid
+/// time signature, see `TimeSignature::validate`.
+///
+/// Since 0.2.0 this returns a `Result` rather than an engine that misbehaves with an
+/// invalid time signature.
 ///
 /// # Arguments
 ///
@@ -96,18 +119,511 @@ pub mod time_signature;
 /// * `denominator` - The lower part of a time signature. Only 2, 4, 8, 16, 32 are supported.
 /// * `bpm` - The beats per minute.
 ///
+/// # Panics
+///
+/// Panics if `bpm` is not finite and greater than zero, see `MusicTimerEngine::new`.
+///
 /// # Example
 ///
 /// ```
-/// let mut performer = music_timer::create_performance_engine(3, 4, 155.0);
+/// let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
+/// assert!(music_timer::create_performance_engine(3, 5, 155.0).is_err());
 /// ```
+#[cfg(feature = "std")]
 pub fn create_performance_engine(
     numerator: u8,
     denominator: u8,
     bpm: f32,
-) -> music_timer_engine::MusicTimerEngine {
-    music_timer_engine::MusicTimerEngine::new(
-        time_signature::TimeSignature::new(numerator, denominator),
-        bpm,
+) -> Result<music_timer_engine::MusicTimerEngine, time_signature::TimeSignatureError> {
+    let time_signature = time_signature::TimeSignature::new(numerator, denominator);
+    time_signature.validate()?;
+    Ok(music_timer_engine::MusicTimerEngine::new(time_signature, bpm))
+}
+
+/// Creates a new music timer performance engine with the tempo in seconds per beat rather
+/// than beats per minute, see `create_performance_engine`.
+///
+/// # Arguments
+///
+/// * `numerator` - The upper part of a time signature. Must be none 0.
+/// * `denominator` - The lower part of a time signature. Only 2, 4, 8, 16, 32 are supported.
+/// * `seconds_per_beat` - The duration of a beat in seconds.
+///
+/// # Panics
+///
+/// Panics if `seconds_per_beat` is not finite and greater than zero, as the bpm it gives
+/// is invalid, see `create_performance_engine`.
+///
+/// # Example
+///
+/// ```
+/// let performer = music_timer::create_performance_engine_spb(3, 4, 0.5).unwrap();
+/// assert_eq!(performer.get_bpm(), 120.0);
+/// ```
+#[cfg(feature = "std")]
+pub fn create_performance_engine_spb(
+    numerator: u8,
+    denominator: u8,
+    seconds_per_beat: f32,
+) -> Result<music_timer_engine::MusicTimerEngine, time_signature::TimeSignatureError> {
+    create_performance_engine(numerator, denominator, 60.0 / seconds_per_beat)
+}
+
+/// Gets the duration of the performance from `a` up to `b` at a constant bpm, _e.g._ for
+/// showing the length of a section. The duration is zero if `b` is before `a`.
+///
+/// # Arguments
+///
+/// * `a` - The music time the section starts at.
+/// * `b` - The music time the section ends at.
+/// * `time_signature` - The time signature to count the beat intervals within.
+/// * `bpm` - The beats per minute.
+///
+/// # Example
+///
+/// ```
+/// use music_timer::{music_time::MusicTime, time_signature::TimeSignature};
+/// use std::time::Duration;
+/// let duration = music_timer::duration_between(
+///     &MusicTime::new(1, 1, 1),
+///     &MusicTime::new(2, 1, 1),
+///     &TimeSignature::new(4, 4),
+///     120.0,
+/// );
+/// assert_eq!(duration, Duration::from_secs(2));
+/// ```
+pub fn duration_between(
+    a: &music_time::MusicTime,
+    b: &music_time::MusicTime,
+    time_signature: &time_signature::TimeSignature,
+    bpm: f32,
+) -> core::time::Duration {
+    let beat_intervals =
+        b.to_beat_interval_index(time_signature) - a.to_beat_interval_index(time_signature);
+    if beat_intervals <= 0 {
+        return core::time::Duration::default();
+    }
+    let beats = beat_intervals as f64 / f64::from(time_signature::DEFAULT_INTERVAL_RESOLUTION);
+    let seconds = beats * 60.0 / f64::from(bpm);
+    core::time::Duration::from_nanos((seconds * 1000000000.0) as u64)
+}
+
+/// Gets the beat interval grid positions immediately before and after a time from the
+/// start of the performance at a constant bpm, _e.g._ to snap a recorded note with your
+/// own policy. The reverse of `duration_between` from `MusicTime::new(1, 1, 1)`. A time
+/// exactly on the grid is both positions, as is any time for a bpm that is not greater
+/// than zero.
+///
+/// # Arguments
+///
+/// * `elapsed` - The time from `MusicTime::new(1, 1, 1)`.
+/// * `time_signature` - The time signature to count the beat intervals within.
+/// * `bpm` - The beats per minute.
+///
+/// # Example
+///
+/// ```
+/// use music_timer::{music_time::MusicTime, time_signature::TimeSignature};
+/// use std::time::Duration;
+/// // A beat interval at 120bpm in 4/4 is 62.5ms
+/// let (before, after) = music_timer::surrounding_positions(
+///     Duration::from_millis(100),
+///     &TimeSignature::new(4, 4),
+///     120.0,
+/// );
+/// assert_eq!(before, MusicTime::new(1, 1, 2));
+/// assert_eq!(after, MusicTime::new(1, 1, 3));
+/// ```
+pub fn surrounding_positions(
+    elapsed: core::time::Duration,
+    time_signature: &time_signature::TimeSignature,
+    bpm: f32,
+) -> (music_time::MusicTime, music_time::MusicTime) {
+    // Times within this many beat intervals of a grid position are on it, so rounding
+    // errors of the conversion do not split a grid position in two
+    const ON_GRID_TOLERANCE: f64 = 0.000001;
+
+    let intervals_per_second =
+        f64::from(bpm) / 60.0 * f64::from(time_signature::DEFAULT_INTERVAL_RESOLUTION);
+    let intervals = (elapsed.as_secs_f64() * intervals_per_second).max(0.0);
+    let nearest = (intervals + 0.5) as i64;
+    let offset = intervals - nearest as f64;
+    let is_on_grid = offset < ON_GRID_TOLERANCE && offset > -ON_GRID_TOLERANCE;
+    let (before, after) = if is_on_grid {
+        (nearest, nearest)
+    } else {
+        (intervals as i64, intervals as i64 + 1)
+    };
+    (
+        music_time::MusicTime::from_beat_interval_index(before, time_signature),
+        music_time::MusicTime::from_beat_interval_index(after, time_signature),
     )
 }
+
+/// Gets the clicks of a metronome over a number of bars, as the time of each click from the
+/// start and whether it is accented on the first beat of a bar. Feed them to any audio
+/// writer to render a click track offline.
+///
+/// # Arguments
+///
+/// * `time_signature` - The time signature of the click track.
+/// * `bpm` - The beats per minute.
+/// * `bars` - The number of bars of clicks.
+///
+/// # Example
+///
+/// ```
+/// use music_timer::time_signature::TimeSignature;
+/// use std::time::Duration;
+/// let clicks = music_timer::click_track_positions(&TimeSignature::new(3, 4), 60.0, 1);
+/// assert_eq!(
+///     clicks,
+///     vec![
+///         (Duration::from_secs(0), true),
+///         (Duration::from_secs(1), false),
+///         (Duration::from_secs(2), false),
+///     ]
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn click_track_positions(
+    time_signature: &time_signature::TimeSignature,
+    bpm: f32,
+    bars: u16,
+) -> Vec<(core::time::Duration, bool)> {
+    let start = music_time::MusicTime::new(1, 1, 1);
+    (1..=bars)
+        .flat_map(|bar| music_time::MusicTime::beats_in_bar(bar, time_signature))
+        .map(|time| {
+            let offset = duration_between(&start, &time, time_signature, bpm);
+            (offset, time.get_beat() == 1)
+        })
+        .collect()
+}
+
+/// Gets the bpm a span of music lasts `duration` at, the reverse of `duration_between`,
+/// _e.g._ for matching the tempo of a sample loop. The span is from
+/// `MusicTime::new(1, 1, 1)` up to `span`, so 2 bars of 4/4 is `MusicTime::new(3, 1, 1)`.
+/// The bpm is `0.0` for an empty span or a zero duration. See
+/// `MusicRange::bpm_for_duration` for spans that start elsewhere.
+///
+/// # Arguments
+///
+/// * `span` - The music time the span ends at.
+/// * `time_signature` - The time signature to count the beat intervals within.
+/// * `duration` - The duration of the span.
+///
+/// # Example
+///
+/// ```
+/// use music_timer::{music_time::MusicTime, time_signature::TimeSignature};
+/// use std::time::Duration;
+/// let bpm = music_timer::bpm_from_span(
+///     &MusicTime::new(2, 1, 1),
+///     &TimeSignature::new(4, 4),
+///     Duration::from_secs(2),
+/// );
+/// assert_eq!(bpm, 120.0);
+/// ```
+pub fn bpm_from_span(
+    span: &music_time::MusicTime,
+    time_signature: &time_signature::TimeSignature,
+    duration: core::time::Duration,
+) -> f32 {
+    music_time::MusicRange::new(music_time::MusicTime::new(1, 1, 1), *span)
+        .bpm_for_duration(time_signature, duration)
+}
+
+/// Gets an endless iterator of the beat interval positions of a looped region, _e.g._ for
+/// the playhead of a drum loop. The positions run from `start` up to and including `end`,
+/// the same as `MusicTimerEngine::set_loop`, then wrap back to `start` forever. Nothing
+/// is yielded if `end` is before `start`.
+///
+/// # Arguments
+///
+/// * `start` - The music time the loop starts at and wraps back to.
+/// * `end` - The last music time of the loop.
+/// * `time_signature` - The time signature to count the beat intervals within.
+///
+/// # Example
+///
+/// ```
+/// use music_timer::{music_time::MusicTime, time_signature::TimeSignature};
+/// let mut positions = music_timer::loop_iter(
+///     MusicTime::new(1, 1, 7),
+///     MusicTime::new(1, 2, 1),
+///     &TimeSignature::new(4, 4),
+/// );
+/// assert_eq!(positions.next(), Some(MusicTime::new(1, 1, 7)));
+/// assert_eq!(positions.next(), Some(MusicTime::new(1, 1, 8)));
+/// assert_eq!(positions.next(), Some(MusicTime::new(1, 2, 1)));
+/// assert_eq!(positions.next(), Some(MusicTime::new(1, 1, 7)));
+/// ```
+pub fn loop_iter(
+    start: music_time::MusicTime,
+    end: music_time::MusicTime,
+    time_signature: &time_signature::TimeSignature,
+) -> impl Iterator<Item = music_time::MusicTime> {
+    let time_signature = *time_signature;
+    let start_index = start.to_beat_interval_index(&time_signature);
+    let end_index = end.to_beat_interval_index(&time_signature);
+    (start_index..=end_index)
+        .cycle()
+        .map(move |index| music_time::MusicTime::from_beat_interval_index(index, &time_signature))
+}
+
+/// Stretches or compresses events in time to fit a new length, _e.g._ to fit a 4 bar
+/// phrase into 3 bars. Each event is scaled by its number of beat intervals from
+/// `MusicTime::new(1, 1, 1)` and rounded to the nearest beat interval, halfway rounding
+/// later. Lengths are spans from `MusicTime::new(1, 1, 1)` the same as `bpm_from_span`, so
+/// 4 bars of 4/4 is `MusicTime::new(5, 1, 1)`. The events are returned as they are if
+/// `from_len` is empty. See `MusicRange::rescale_events` for ranges that start elsewhere.
+///
+/// # Arguments
+///
+/// * `events` - The music times of the events.
+/// * `from_len` - The length the events are within.
+/// * `to_len` - The length to fit the events to.
+/// * `time_signature` - The time signature to count the beat intervals within.
+///
+/// # Example
+///
+/// ```
+/// use music_timer::{music_time::MusicTime, time_signature::TimeSignature};
+/// let events = [MusicTime::new(1, 1, 1), MusicTime::new(3, 1, 1), MusicTime::new(4, 3, 1)];
+/// let fitted = music_timer::rescale_events(
+///     &events,
+///     &MusicTime::new(5, 1, 1),
+///     &MusicTime::new(4, 1, 1),
+///     &TimeSignature::new(4, 4),
+/// );
+/// assert_eq!(
+///     fitted,
+///     vec![MusicTime::new(1, 1, 1), MusicTime::new(2, 3, 1), MusicTime::new(3, 3, 5)]
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn rescale_events(
+    events: &[music_time::MusicTime],
+    from_len: &music_time::MusicTime,
+    to_len: &music_time::MusicTime,
+    time_signature: &time_signature::TimeSignature,
+) -> Vec<music_time::MusicTime> {
+    let start = music_time::MusicTime::new(1, 1, 1);
+    let from = music_time::MusicRange::new(start, *from_len);
+    let to = music_time::MusicRange::new(start, *to_len);
+    from.rescale_events(events, &to, time_signature)
+}
+
+mod tests {
+    #[test]
+    fn test_duration_between() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+        use std::time::Duration;
+
+        let time_signature = TimeSignature::new(4, 4);
+        let start = MusicTime::new(1, 1, 1);
+        let end = MusicTime::new(3, 1, 1);
+        assert_eq!(
+            crate::duration_between(&start, &end, &time_signature, 100.0),
+            Duration::from_millis(4800)
+        );
+
+        let end = MusicTime::new(2, 3, 5);
+        assert_eq!(
+            crate::duration_between(&start, &end, &time_signature, 100.0),
+            Duration::from_millis(3900)
+        );
+        assert_eq!(
+            crate::duration_between(&end, &start, &time_signature, 100.0),
+            Duration::default()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_click_track_positions() {
+        use crate::time_signature::TimeSignature;
+        use std::time::Duration;
+
+        let clicks = crate::click_track_positions(&TimeSignature::new(4, 4), 120.0, 2);
+        assert_eq!(clicks.len(), 8);
+        for (click, (offset, is_accented)) in clicks.iter().enumerate() {
+            assert_eq!(*offset, Duration::from_millis(500) * click as u32);
+            assert_eq!(*is_accented, click % 4 == 0);
+        }
+
+        assert!(crate::click_track_positions(&TimeSignature::new(4, 4), 120.0, 0).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_create_performance_engine_spb() {
+        let spb_performer = crate::create_performance_engine_spb(4, 4, 0.5).unwrap();
+        let bpm_performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+        assert_eq!(
+            spb_performer.get_beat_interval_duration(),
+            bpm_performer.get_beat_interval_duration()
+        );
+        assert!(crate::create_performance_engine_spb(4, 5, 0.5).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    #[should_panic(expected = "The bpm must be finite and greater than zero, got inf")]
+    fn test_create_performance_engine_zero_spb() {
+        let _ = crate::create_performance_engine_spb(4, 4, 0.0);
+    }
+
+    #[test]
+    fn test_surrounding_positions() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+        use core::time::Duration;
+
+        // A beat interval at 90bpm in 3/4 is 1/12 of a second
+        let time_signature = TimeSignature::new(3, 4);
+        let between = Duration::from_millis(1000 + 1000 / 24);
+        assert_eq!(
+            crate::surrounding_positions(between, &time_signature, 90.0),
+            (MusicTime::new(1, 2, 5), MusicTime::new(1, 2, 6))
+        );
+        let late = Duration::from_millis(1000 + 1000 / 12 - 1);
+        assert_eq!(
+            crate::surrounding_positions(late, &time_signature, 90.0),
+            (MusicTime::new(1, 2, 5), MusicTime::new(1, 2, 6))
+        );
+
+        // On the grid, the reverse of duration_between
+        let on_grid = MusicTime::new(3, 2, 7);
+        let elapsed =
+            crate::duration_between(&MusicTime::new(1, 1, 1), &on_grid, &time_signature, 90.0);
+        assert_eq!(
+            crate::surrounding_positions(elapsed, &time_signature, 90.0),
+            (on_grid, on_grid)
+        );
+        assert_eq!(
+            crate::surrounding_positions(Duration::default(), &time_signature, 90.0),
+            (MusicTime::new(1, 1, 1), MusicTime::new(1, 1, 1))
+        );
+        assert_eq!(
+            crate::surrounding_positions(between, &time_signature, 0.0),
+            (MusicTime::new(1, 1, 1), MusicTime::new(1, 1, 1))
+        );
+    }
+
+    #[test]
+    fn test_bpm_from_span() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+        use std::time::Duration;
+
+        // 2 bars of 4/4 in 4 seconds
+        let time_signature = TimeSignature::new(4, 4);
+        let span = MusicTime::new(3, 1, 1);
+        let bpm = crate::bpm_from_span(&span, &time_signature, Duration::from_secs(4));
+        assert_eq!(bpm, 120.0);
+
+        // The reverse of duration_between
+        let start = MusicTime::new(1, 1, 1);
+        let duration = crate::duration_between(&start, &span, &time_signature, 97.0);
+        let bpm = crate::bpm_from_span(&span, &time_signature, duration);
+        assert!((bpm - 97.0).abs() < 0.001);
+
+        let time_signature = TimeSignature::new(6, 8);
+        let span = MusicTime::new(2, 4, 1);
+        let bpm = crate::bpm_from_span(&span, &time_signature, Duration::from_secs(3));
+        assert_eq!(bpm, 180.0);
+
+        assert_eq!(
+            crate::bpm_from_span(&start, &time_signature, Duration::from_secs(4)),
+            0.0
+        );
+        assert_eq!(
+            crate::bpm_from_span(&span, &time_signature, Duration::default()),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_loop_iter() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+
+        let time_signature = TimeSignature::new(4, 4);
+        let start = MusicTime::new(3, 4, 1);
+        let end = MusicTime::new(4, 1, 8);
+        let loop_length = 16;
+
+        // Two and a half loops
+        let positions: Vec<MusicTime> = crate::loop_iter(start, end, &time_signature)
+            .take(loop_length * 5 / 2)
+            .collect();
+        assert_eq!(positions.len(), 40);
+        assert_eq!(positions[0], start);
+        assert_eq!(positions[7], MusicTime::new(3, 4, 8));
+        assert_eq!(positions[8], MusicTime::new(4, 1, 1));
+        assert_eq!(positions[15], end);
+        assert_eq!(positions[16], start);
+        assert_eq!(positions[31], end);
+        assert_eq!(positions[32], start);
+        assert_eq!(positions[39], MusicTime::new(3, 4, 8));
+        assert_eq!(positions[..16], positions[16..32]);
+
+        // A single position repeats and a backwards region is empty
+        let mut single = crate::loop_iter(start, start, &time_signature);
+        assert_eq!(single.next(), Some(start));
+        assert_eq!(single.next(), Some(start));
+        assert_eq!(crate::loop_iter(end, start, &time_signature).next(), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_rescale_events() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+
+        let time_signature = TimeSignature::new(4, 4);
+        let four_bars = MusicTime::new(5, 1, 1);
+        let two_bars = MusicTime::new(3, 1, 1);
+        let events = vec![
+            MusicTime::new(1, 1, 1),
+            MusicTime::new(1, 3, 1),
+            MusicTime::new(2, 1, 1),
+            MusicTime::new(3, 2, 5),
+            MusicTime::new(4, 4, 8),
+        ];
+
+        // Compressing 4 bars into 2 halves the positions
+        let compressed = crate::rescale_events(&events, &four_bars, &two_bars, &time_signature);
+        assert_eq!(
+            compressed,
+            vec![
+                MusicTime::new(1, 1, 1),
+                MusicTime::new(1, 2, 1),
+                MusicTime::new(1, 3, 1),
+                MusicTime::new(2, 1, 7),
+                // Halfway rounds later
+                MusicTime::new(3, 1, 1),
+            ]
+        );
+        for (event, scaled) in events.iter().zip(compressed.iter()) {
+            let index = event.to_beat_interval_index(&time_signature);
+            assert_eq!(
+                scaled.to_beat_interval_index(&time_signature),
+                (index + 1) / 2
+            );
+        }
+
+        // Stretching back restores the events that were on the grid
+        let stretched = crate::rescale_events(&compressed, &two_bars, &four_bars, &time_signature);
+        assert_eq!(stretched[..4], events[..4]);
+
+        // An empty length to scale from leaves the events as they are
+        assert_eq!(
+            crate::rescale_events(
+                &events,
+                &MusicTime::new(1, 1, 1),
+                &two_bars,
+                &time_signature
+            ),
+            events
+        );
+    }
+}
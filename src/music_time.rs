@@ -4,11 +4,53 @@
 //! Data structure that holds music time and logic when advancing beats and beat intervals.
 //!
 
-use super::time_signature::TimeSignature;
-use std::cmp::Ordering;
+use super::time_signature::{TimeSignature, DEFAULT_INTERVAL_RESOLUTION};
+use core::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Sub},
+    time::Duration,
+};
 
-#[derive(Clone, Copy, Debug, Eq, Ord)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Errors that can occur when setting a music time within a time signature.
+pub enum MusicTimeError {
+    /// The beat is 0 or exceeds the numerator of the time signature.
+    BeatOutOfRange,
+    /// The beat interval is 0 or exceeds the beat intervals per beat of the time signature.
+    BeatIntervalOutOfRange,
+}
+
+impl fmt::Display for MusicTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MusicTimeError::BeatOutOfRange => {
+                write!(f, "The beat must be from 1 up to the numerator")
+            }
+            MusicTimeError::BeatIntervalOutOfRange => {
+                write!(
+                    f,
+                    "The beat interval must be from 1 up to the intervals per beat"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MusicTimeError {}
+
+#[derive(Clone, Copy, Debug, Eq, PartialOrd, Ord)]
 /// Data structure that holds music time and logic when advancing beats and beat intervals.
+///
+/// Bars count from `1`. Bar `0` is the pre-roll bar before the performance, for count ins
+/// and pickup notes, so `MusicTime::new(0, 4, 8)` advances into `MusicTime::new(1, 1, 1)`
+/// in 4/4 and retreats back again.
+///
+/// The ordering compares the bar, then the beat, then the beat interval. This is only
+/// correct when the beats and beat intervals are within the time signature, _e.g._
+/// `MusicTime::new(1, 5, 1)` orders before `MusicTime::new(2, 1, 1)` though it is later
+/// in 3/4. Use `cmp_in` to compare within a time signature.
 pub struct MusicTime {
     bar: u16,
     beat: u8,
@@ -35,6 +77,30 @@ impl MusicTime {
         }
     }
 
+    /// Create a new `MusicTime`, or `None` if it can never be reached. Beats and beat
+    /// intervals count from `1`, and a beat has at most `DEFAULT_INTERVAL_RESOLUTION` beat
+    /// intervals. Use `set_beat` and `set_beat_interval` to check against a particular
+    /// time signature.
+    ///
+    /// # Arguments
+    /// * `bar` - The musical bar.
+    /// * `beat` - The musical beat.
+    /// * `beat_interval` - The musical beat interval, the subdivisions of a beat.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::music_time::MusicTime;
+    /// assert_eq!(MusicTime::checked_new(1, 2, 3), Some(MusicTime::new(1, 2, 3)));
+    /// assert_eq!(MusicTime::checked_new(1, 0, 3), None);
+    /// ```
+    pub fn checked_new(bar: u16, beat: u8, beat_interval: u8) -> Option<MusicTime> {
+        if beat == 0 || beat_interval == 0 || beat_interval > DEFAULT_INTERVAL_RESOLUTION {
+            None
+        } else {
+            Some(MusicTime::new(bar, beat, beat_interval))
+        }
+    }
+
     /// Get the bar number.
     pub fn get_bar(&self) -> u16 {
         self.bar
@@ -50,8 +116,150 @@ impl MusicTime {
         self.beat_interval
     }
 
+    /// Pack the music time into 4 bytes, for a stable binary format. The bar is the first 2
+    /// bytes in little-endian order, followed by the beat and then the beat interval.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::music_time::MusicTime;
+    /// assert_eq!(MusicTime::new(258, 3, 7).to_bytes(), [2, 1, 3, 7]);
+    /// ```
+    pub fn to_bytes(&self) -> [u8; 4] {
+        let [bar_low, bar_high] = self.bar.to_le_bytes();
+        [bar_low, bar_high, self.beat, self.beat_interval]
+    }
+
+    /// Unpack a music time from the 4 bytes of `to_bytes`.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bar in little-endian order, the beat and the beat interval.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::music_time::MusicTime;
+    /// assert_eq!(MusicTime::from_bytes([2, 1, 3, 7]), MusicTime::new(258, 3, 7));
+    /// ```
+    pub fn from_bytes(bytes: [u8; 4]) -> MusicTime {
+        MusicTime {
+            bar: u16::from_le_bytes([bytes[0], bytes[1]]),
+            beat: bytes[2],
+            beat_interval: bytes[3],
+        }
+    }
+
+    /// Set the bar number.
+    ///
+    /// # Arguments
+    /// * `bar` - The musical bar.
+    pub fn set_bar(&mut self, bar: u16) -> &mut Self {
+        self.bar = bar;
+        self
+    }
+
+    /// Set the beat number, rejecting a beat the time signature never reaches.
+    ///
+    /// # Arguments
+    /// * `beat` - The musical beat, from `1` up to the numerator of `time_signature`.
+    /// * `time_signature` - The time signature to constrain the beat by.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{
+    ///     music_time::{MusicTime, MusicTimeError},
+    ///     time_signature::TimeSignature,
+    /// };
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// let mut time = MusicTime::new(1, 1, 1);
+    /// assert_eq!(time.set_beat(4, &time_signature), Ok(()));
+    /// assert_eq!(time.set_beat(9, &time_signature), Err(MusicTimeError::BeatOutOfRange));
+    /// assert_eq!(time, MusicTime::new(1, 4, 1));
+    /// ```
+    pub fn set_beat(
+        &mut self,
+        beat: u8,
+        time_signature: &TimeSignature,
+    ) -> Result<(), MusicTimeError> {
+        if beat == 0 || beat > time_signature.get_numerator() {
+            return Err(MusicTimeError::BeatOutOfRange);
+        }
+        self.beat = beat;
+        Ok(())
+    }
+
+    /// Set the beat interval, rejecting a beat interval the time signature never reaches.
+    ///
+    /// # Arguments
+    /// * `beat_interval` - The musical beat interval, from `1` up to
+    ///   `DEFAULT_INTERVAL_RESOLUTION`.
+    /// * `_time_signature` - The time signature of the music time. Every time signature
+    ///   has the same number of beat intervals per beat.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{
+    ///     music_time::{MusicTime, MusicTimeError},
+    ///     time_signature::TimeSignature,
+    /// };
+    /// let time_signature = TimeSignature::new(6, 8);
+    /// let mut time = MusicTime::new(1, 1, 1);
+    /// assert_eq!(time.set_beat_interval(8, &time_signature), Ok(()));
+    /// assert_eq!(
+    ///     time.set_beat_interval(9, &time_signature),
+    ///     Err(MusicTimeError::BeatIntervalOutOfRange)
+    /// );
+    /// ```
+    pub fn set_beat_interval(
+        &mut self,
+        beat_interval: u8,
+        _time_signature: &TimeSignature,
+    ) -> Result<(), MusicTimeError> {
+        if beat_interval == 0 || beat_interval > DEFAULT_INTERVAL_RESOLUTION {
+            return Err(MusicTimeError::BeatIntervalOutOfRange);
+        }
+        self.beat_interval = beat_interval;
+        Ok(())
+    }
+
+    /// Gets the music time `n` bars later, keeping the beat and beat interval. The bar
+    /// saturates at `u16::MAX`, see `checked_add_bars`.
+    ///
+    /// # Arguments
+    /// * `n` - The number of bars to move later by.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::music_time::MusicTime;
+    /// assert_eq!(MusicTime::new(2, 3, 5).add_bars(4), MusicTime::new(6, 3, 5));
+    /// assert_eq!(MusicTime::new(u16::MAX, 1, 1).add_bars(1), MusicTime::new(u16::MAX, 1, 1));
+    /// ```
+    pub fn add_bars(&self, n: u16) -> MusicTime {
+        MusicTime {
+            bar: self.bar.saturating_add(n),
+            ..*self
+        }
+    }
+
+    /// Gets the music time `n` bars later, keeping the beat and beat interval, or `None`
+    /// if the bar would overflow `u16::MAX`.
+    ///
+    /// # Arguments
+    /// * `n` - The number of bars to move later by.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::music_time::MusicTime;
+    /// assert_eq!(MusicTime::new(2, 3, 5).checked_add_bars(4), Some(MusicTime::new(6, 3, 5)));
+    /// assert_eq!(MusicTime::new(u16::MAX, 1, 1).checked_add_bars(1), None);
+    /// ```
+    pub fn checked_add_bars(&self, n: u16) -> Option<MusicTime> {
+        self.bar
+            .checked_add(n)
+            .map(|bar| MusicTime { bar, ..*self })
+    }
+
     /// Advance the beat by 1. The bar number will increase if the beat
-    /// exceeds the `TimeSignature` numerator.
+    /// exceeds the `TimeSignature` numerator. Advancing past the last beat of bar
+    /// `u16::MAX` wraps around to the pre-roll bar `0`, see `saturating_advance_beat`.
     ///
     /// # Arguments
     /// * `time_signature` - The time signature to constrain the music time by.
@@ -74,15 +282,36 @@ impl MusicTime {
     pub fn advance_beat(&mut self, time_signature: &TimeSignature) {
         if self.beat >= time_signature.get_numerator() {
             self.beat = 1;
-            self.bar += 1;
+            self.bar = self.bar.wrapping_add(1);
         } else {
             self.beat += 1;
         }
     }
 
+    /// Advance the beat by 1, the same as `advance_beat` but the music time stays at the
+    /// last beat of bar `u16::MAX` rather than wrapping around to bar `0`.
+    ///
+    /// # Arguments
+    /// * `time_signature` - The time signature to constrain the music time by.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// let mut a = MusicTime::new(u16::MAX, 4, 1);
+    /// a.saturating_advance_beat(&time_signature);
+    /// assert_eq!(a, MusicTime::new(u16::MAX, 4, 1));
+    /// ```
+    pub fn saturating_advance_beat(&mut self, time_signature: &TimeSignature) {
+        let is_last_beat = self.bar == u16::MAX && self.beat >= time_signature.get_numerator();
+        if !is_last_beat {
+            self.advance_beat(time_signature);
+        }
+    }
+
     /// Advance the beat interval by 1. The beat number will increase if the beat interval
-    /// exceeds the the interval resolution of `8`. Then The bar number will increase if the beat
-    /// exceeds the `TimeSignature` numerator.
+    /// exceeds `DEFAULT_INTERVAL_RESOLUTION` of `8`. Then The bar number will
+    /// increase if the beat exceeds the `TimeSignature` numerator.
     ///
     /// # Arguments
     /// * `time_signature` - The time signature to constrain the music time by.
@@ -106,14 +335,481 @@ impl MusicTime {
     /// assert_eq!(a, MusicTime::new(1, 2, 1));
     /// ```
     pub fn advance_beat_interval(&mut self, time_signature: &TimeSignature) {
-        const INTERVAL_RESOLUTION: u8 = 16;
-        if self.beat_interval >= INTERVAL_RESOLUTION / 2 {
+        self.advance_beat_interval_with_resolution(time_signature, DEFAULT_INTERVAL_RESOLUTION);
+    }
+
+    /// Advance the beat interval by 1, the same as `advance_beat_interval` but with a beat
+    /// divided into `resolution` beat intervals, _e.g._ `3` for triplets.
+    ///
+    /// # Arguments
+    /// * `time_signature` - The time signature to constrain the music time by.
+    /// * `resolution` - The number of beat intervals in a beat.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// let mut a = MusicTime::new(1, 1, 3);
+    /// a.advance_beat_interval_with_resolution(&time_signature, 3);
+    /// assert_eq!(a, MusicTime::new(1, 2, 1));
+    /// ```
+    pub fn advance_beat_interval_with_resolution(
+        &mut self,
+        time_signature: &TimeSignature,
+        resolution: u8,
+    ) {
+        if self.beat_interval >= resolution {
             self.beat_interval = 1;
             self.advance_beat(time_signature);
         } else {
             self.beat_interval += 1;
         }
     }
+
+    /// Retreat the beat by 1, the reverse of `advance_beat`. The bar number will decrease
+    /// if the beat is the first of the bar, into the pre-roll bar `0`. Retreating from the
+    /// first beat of bar `0` stays put.
+    ///
+    /// # Arguments
+    /// * `time_signature` - The time signature to constrain the music time by.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(3, 4);
+    /// let mut a = MusicTime::new(1, 1, 1);
+    /// a.retreat_beat(&time_signature);
+    /// assert_eq!(a, MusicTime::new(0, 3, 1));
+    /// ```
+    pub fn retreat_beat(&mut self, time_signature: &TimeSignature) {
+        if self.beat > 1 {
+            self.beat -= 1;
+        } else if self.bar > 0 {
+            self.beat = time_signature.get_numerator();
+            self.bar -= 1;
+        }
+    }
+
+    /// Retreat the beat interval by 1, the reverse of `advance_beat_interval`. Retreating
+    /// from the first beat interval of bar `0` stays put.
+    ///
+    /// # Arguments
+    /// * `time_signature` - The time signature to constrain the music time by.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// let mut a = MusicTime::new(1, 1, 1);
+    /// a.retreat_beat_interval(&time_signature);
+    /// assert_eq!(a, MusicTime::new(0, 4, 8));
+    /// ```
+    pub fn retreat_beat_interval(&mut self, time_signature: &TimeSignature) {
+        if self.beat_interval > 1 {
+            self.beat_interval -= 1;
+        } else if self.bar > 0 || self.beat > 1 {
+            self.beat_interval = DEFAULT_INTERVAL_RESOLUTION;
+            self.retreat_beat(time_signature);
+        }
+    }
+
+    /// Gets the start of the bar this music time is in.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::music_time::MusicTime;
+    /// assert_eq!(MusicTime::new(3, 2, 5).floor_to_bar(), MusicTime::new(3, 1, 1));
+    /// ```
+    pub fn floor_to_bar(&self) -> MusicTime {
+        MusicTime::new(self.bar, 1, 1)
+    }
+
+    /// Gets the start of the beat this music time is in.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::music_time::MusicTime;
+    /// assert_eq!(MusicTime::new(3, 2, 5).floor_to_beat(), MusicTime::new(3, 2, 1));
+    /// ```
+    pub fn floor_to_beat(&self) -> MusicTime {
+        MusicTime::new(self.bar, self.beat, 1)
+    }
+
+    /// Gets the start of the next bar, or this music time if it is already the start
+    /// of a bar.
+    ///
+    /// # Arguments
+    /// * `time_signature` - The time signature to constrain the music time by.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// let a = MusicTime::new(3, 2, 5);
+    /// assert_eq!(a.ceil_to_bar(&time_signature), MusicTime::new(4, 1, 1));
+    /// ```
+    pub fn ceil_to_bar(&self, time_signature: &TimeSignature) -> MusicTime {
+        let mut time = self.ceil_to_beat(time_signature);
+        while time.beat != 1 {
+            time.advance_beat(time_signature);
+        }
+        time
+    }
+
+    /// Gets the start of the next beat, or this music time if it is already the start
+    /// of a beat.
+    ///
+    /// # Arguments
+    /// * `time_signature` - The time signature to constrain the music time by.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// let a = MusicTime::new(3, 2, 5);
+    /// assert_eq!(a.ceil_to_beat(&time_signature), MusicTime::new(3, 3, 1));
+    /// ```
+    pub fn ceil_to_beat(&self, time_signature: &TimeSignature) -> MusicTime {
+        let mut time = self.floor_to_beat();
+        if time != *self {
+            time.advance_beat(time_signature);
+        }
+        time
+    }
+
+    /// Gets the first beat interval of every beat in a bar, from `(bar, 1, 1)` up to
+    /// `(bar, numerator, 1)`. Handy for drawing a grid of the bar.
+    ///
+    /// # Arguments
+    /// * `bar` - The bar to get the beats of.
+    /// * `time_signature` - The time signature to count the beats within.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let beats = MusicTime::beats_in_bar(2, &TimeSignature::new(3, 4));
+    /// assert_eq!(
+    ///     beats,
+    ///     vec![MusicTime::new(2, 1, 1), MusicTime::new(2, 2, 1), MusicTime::new(2, 3, 1)]
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn beats_in_bar(bar: u16, time_signature: &TimeSignature) -> Vec<MusicTime> {
+        (1..=time_signature.get_numerator())
+            .map(|beat| MusicTime::new(bar, beat, 1))
+            .collect()
+    }
+
+    /// Gets the number of beat intervals from `MusicTime::new(1, 1, 1)` to this music time,
+    /// negative for music times before bar 1. The beat intervals are counted at the interval
+    /// resolution of `DEFAULT_INTERVAL_RESOLUTION`.
+    ///
+    /// # Arguments
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(3, 4);
+    /// assert_eq!(MusicTime::new(1, 1, 1).to_beat_interval_index(&time_signature), 0);
+    /// assert_eq!(MusicTime::new(2, 1, 2).to_beat_interval_index(&time_signature), 25);
+    /// ```
+    pub fn to_beat_interval_index(&self, time_signature: &TimeSignature) -> i64 {
+        let intervals_per_beat = i64::from(DEFAULT_INTERVAL_RESOLUTION);
+        let beats_per_bar = i64::from(time_signature.get_numerator());
+        let beats = (i64::from(self.bar) - 1) * beats_per_bar + i64::from(self.beat) - 1;
+        beats * intervals_per_beat + i64::from(self.beat_interval) - 1
+    }
+
+    /// Create a `MusicTime` from a number of beat intervals from `MusicTime::new(1, 1, 1)`,
+    /// the reverse of `to_beat_interval_index`. Indexes before the pre-roll bar `0` are
+    /// clamped to its first beat interval and indexes past bar `u16::MAX` are clamped to
+    /// its last beat interval. A time signature with no beats per bar has no positions, so
+    /// `MusicTime::default()` is returned.
+    ///
+    /// # Arguments
+    /// * `index` - The number of beat intervals from `MusicTime::new(1, 1, 1)`.
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(3, 4);
+    /// assert_eq!(MusicTime::from_beat_interval_index(25, &time_signature), MusicTime::new(2, 1, 2));
+    /// ```
+    pub fn from_beat_interval_index(index: i64, time_signature: &TimeSignature) -> MusicTime {
+        let intervals_per_beat = i64::from(DEFAULT_INTERVAL_RESOLUTION);
+        let beats_per_bar = i64::from(time_signature.get_numerator());
+        if beats_per_bar == 0 {
+            return MusicTime::default();
+        }
+        let intervals_per_bar = beats_per_bar * intervals_per_beat;
+        let last_index = i64::from(u16::MAX) * intervals_per_bar - 1;
+        let index = index.max(-intervals_per_bar).min(last_index);
+        let beats = index.div_euclid(intervals_per_beat);
+        let bar = (beats.div_euclid(beats_per_bar) + 1).min(i64::from(u16::MAX));
+        MusicTime {
+            bar: bar as u16,
+            beat: (beats.rem_euclid(beats_per_bar) + 1) as u8,
+            beat_interval: (index.rem_euclid(intervals_per_beat) + 1) as u8,
+        }
+    }
+
+    /// Gets this music time as a number of bars, with the beat and beat interval as the
+    /// fraction through the bar, _e.g._ for plotting. Bars are one-based like `MusicTime`,
+    /// so `MusicTime::new(1, 1, 1)` is `1.0` and halfway through bar 2 is `2.5`.
+    ///
+    /// # Arguments
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// assert_eq!(MusicTime::new(2, 3, 1).as_float_bars(&time_signature), 2.5);
+    /// ```
+    pub fn as_float_bars(&self, time_signature: &TimeSignature) -> f64 {
+        let intervals_per_bar =
+            f64::from(time_signature.get_numerator()) * f64::from(DEFAULT_INTERVAL_RESOLUTION);
+        let index = self.to_beat_interval_index(time_signature) as f64;
+        1.0 + index / intervals_per_bar.max(1.0)
+    }
+
+    /// Create a `MusicTime` from a one-based number of bars, the reverse of
+    /// `as_float_bars`. The fraction is rounded to the nearest beat interval.
+    ///
+    /// # Arguments
+    /// * `bars` - The number of bars, `1.0` being `MusicTime::new(1, 1, 1)`.
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// assert_eq!(MusicTime::from_float_bars(2.5, &time_signature), MusicTime::new(2, 3, 1));
+    /// ```
+    pub fn from_float_bars(bars: f64, time_signature: &TimeSignature) -> MusicTime {
+        let intervals_per_bar =
+            f64::from(time_signature.get_numerator()) * f64::from(DEFAULT_INTERVAL_RESOLUTION);
+        let index = (bars - 1.0) * intervals_per_bar;
+        // Round half away from zero, `f64::round` needs std
+        let index = if index >= 0.0 {
+            (index + 0.5) as i64
+        } else {
+            (index - 0.5) as i64
+        };
+        MusicTime::from_beat_interval_index(index, time_signature)
+    }
+
+    /// Gets the number of ticks from `MusicTime::new(1, 1, 1)` to this music time, at a
+    /// resolution of `ppqn` ticks per quarter note as used by MIDI files and DAWs. Music
+    /// times in the pre-roll bar `0` are `0` ticks.
+    ///
+    /// # Arguments
+    /// * `time_signature` - The time signature to count the ticks within.
+    /// * `ppqn` - The pulses per quarter note, _e.g._ `480` or `960`.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// assert_eq!(MusicTime::new(2, 1, 5).to_ppqn(&time_signature, 480), 4 * 480 + 240);
+    /// ```
+    pub fn to_ppqn(&self, time_signature: &TimeSignature, ppqn: u32) -> u64 {
+        let index = self.to_beat_interval_index(time_signature).max(0) as u128;
+        let intervals_per_whole_note =
+            u128::from(time_signature.get_denominator()) * u128::from(DEFAULT_INTERVAL_RESOLUTION);
+        let ticks = index * 4 * u128::from(ppqn) / intervals_per_whole_note.max(1);
+        ticks.min(u128::from(u64::MAX)) as u64
+    }
+
+    /// Create a `MusicTime` from a number of ticks from `MusicTime::new(1, 1, 1)`, the
+    /// reverse of `to_ppqn`. Ticks between beat intervals are rounded down.
+    ///
+    /// # Arguments
+    /// * `ticks` - The number of ticks from `MusicTime::new(1, 1, 1)`.
+    /// * `time_signature` - The time signature to count the ticks within.
+    /// * `ppqn` - The pulses per quarter note, _e.g._ `480` or `960`.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// assert_eq!(MusicTime::from_ppqn(4 * 480 + 240, &time_signature, 480), MusicTime::new(2, 1, 5));
+    /// ```
+    pub fn from_ppqn(ticks: u64, time_signature: &TimeSignature, ppqn: u32) -> MusicTime {
+        let intervals_per_whole_note =
+            u128::from(time_signature.get_denominator()) * u128::from(DEFAULT_INTERVAL_RESOLUTION);
+        let index = u128::from(ticks) * intervals_per_whole_note / (4 * u128::from(ppqn)).max(1);
+        MusicTime::from_beat_interval_index(index.min(i64::MAX as u128) as i64, time_signature)
+    }
+
+    /// Gets the time from `MusicTime::new(1, 1, 1)` to this music time as an exact fraction
+    /// of seconds, `(numerator, denominator)` in lowest terms. The bpm is a fraction too, so
+    /// no rounding errors accumulate over long performances. Music times in the pre-roll
+    /// bar `0` are `(0, 1)`, as is a bpm of `0`.
+    ///
+    /// # Arguments
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    /// * `bpm_num` - The numerator of the beats per minute.
+    /// * `bpm_den` - The denominator of the beats per minute.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// // 2 beats at 90bpm is 4/3 seconds
+    /// assert_eq!(MusicTime::new(1, 3, 1).exact_offset(&time_signature, 90, 1), (4, 3));
+    /// ```
+    pub fn exact_offset(
+        &self,
+        time_signature: &TimeSignature,
+        bpm_num: u32,
+        bpm_den: u32,
+    ) -> (u64, u64) {
+        let index = self.to_beat_interval_index(time_signature).max(0) as u128;
+        let numerator = index * 60 * u128::from(bpm_den);
+        let denominator = u128::from(DEFAULT_INTERVAL_RESOLUTION) * u128::from(bpm_num);
+        if numerator == 0 || denominator == 0 {
+            return (0, 1);
+        }
+        let divisor = gcd(numerator, denominator);
+        let to_u64 = |value: u128| value.min(u128::from(u64::MAX)) as u64;
+        (to_u64(numerator / divisor), to_u64(denominator / divisor))
+    }
+
+    /// Round this music time to the nearest multiple of `grid_intervals` beat intervals from
+    /// `MusicTime::new(1, 1, 1)`, _e.g._ `8` snaps to the nearest beat and `16` to every 2
+    /// beats. Halfway music times round later. A grid of `0` leaves the music time as-is.
+    ///
+    /// # Arguments
+    /// * `grid_intervals` - The number of beat intervals between grid lines.
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// let a = MusicTime::new(1, 2, 6);
+    /// assert_eq!(a.quantize_to_grid(8, &time_signature), MusicTime::new(1, 3, 1));
+    /// assert_eq!(a.quantize_to_grid(16, &time_signature), MusicTime::new(1, 3, 1));
+    /// ```
+    pub fn quantize_to_grid(
+        &self,
+        grid_intervals: u64,
+        time_signature: &TimeSignature,
+    ) -> MusicTime {
+        if grid_intervals == 0 {
+            return *self;
+        }
+        let grid = grid_intervals.min(i64::MAX as u64) as i64;
+        let index = self.to_beat_interval_index(time_signature);
+        let snapped = (index + grid / 2).div_euclid(grid) * grid;
+        MusicTime::from_beat_interval_index(snapped, time_signature)
+    }
+
+    /// Gets the music time `delta` beat intervals later, carrying into the beats and bars
+    /// of the time signature.
+    ///
+    /// # Arguments
+    /// * `delta` - The number of beat intervals to move later by.
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// let a = MusicTime::new(1, 4, 7);
+    /// assert_eq!(a.offset_by(3, &time_signature), MusicTime::new(2, 1, 2));
+    /// ```
+    pub fn offset_by(&self, delta: u64, time_signature: &TimeSignature) -> MusicTime {
+        let delta = delta.min(i64::MAX as u64) as i64;
+        let index = self.to_beat_interval_index(time_signature);
+        MusicTime::from_beat_interval_index(index.saturating_add(delta), time_signature)
+    }
+
+    /// Compare this music time to `other` by the number of beat intervals from
+    /// `MusicTime::new(1, 1, 1)` within a time signature. Unlike the field-wise ordering
+    /// this is correct when the beats or beat intervals overflow the time signature.
+    ///
+    /// # Arguments
+    /// * `other` - The music time to compare to.
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// use std::cmp::Ordering;
+    /// let time_signature = TimeSignature::new(3, 4);
+    /// let a = MusicTime::new(1, 5, 1);
+    /// let b = MusicTime::new(2, 1, 1);
+    /// assert!(a < b);
+    /// assert_eq!(a.cmp_in(&b, &time_signature), Ordering::Greater);
+    /// ```
+    pub fn cmp_in(&self, other: &MusicTime, time_signature: &TimeSignature) -> Ordering {
+        self.to_beat_interval_index(time_signature)
+            .cmp(&other.to_beat_interval_index(time_signature))
+    }
+
+    /// Returns `true` if this music time is within `tolerance_intervals` beat intervals
+    /// of `other`, either side.
+    ///
+    /// # Arguments
+    /// * `other` - The music time to compare to.
+    /// * `tolerance_intervals` - The most beat intervals apart the music times can be.
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time::MusicTime};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// let a = MusicTime::new(1, 4, 8);
+    /// assert!(a.within(&MusicTime::new(2, 1, 1), 1, &time_signature));
+    /// ```
+    pub fn within(
+        &self,
+        other: &MusicTime,
+        tolerance_intervals: u64,
+        time_signature: &TimeSignature,
+    ) -> bool {
+        let distance = self.to_beat_interval_index(time_signature)
+            - other.to_beat_interval_index(time_signature);
+        distance.unsigned_abs() <= tolerance_intervals
+    }
+}
+
+/// Gets the greatest common divisor of `a` and `b`.
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let remainder = a % b;
+        a = b;
+        b = remainder;
+    }
+    a
+}
+
+impl Add<MusicTimeDelta> for MusicTime {
+    type Output = MusicTime;
+
+    fn add(self, delta: MusicTimeDelta) -> MusicTime {
+        let index = self.to_beat_interval_index(&delta.time_signature);
+        MusicTime::from_beat_interval_index(
+            index.saturating_add(delta.intervals),
+            &delta.time_signature,
+        )
+    }
+}
+
+impl Sub<MusicTimeDelta> for MusicTime {
+    type Output = MusicTime;
+
+    fn sub(self, delta: MusicTimeDelta) -> MusicTime {
+        let index = self.to_beat_interval_index(&delta.time_signature);
+        MusicTime::from_beat_interval_index(
+            index.saturating_sub(delta.intervals),
+            &delta.time_signature,
+        )
+    }
 }
 
 impl PartialEq for MusicTime {
@@ -124,14 +820,6 @@ impl PartialEq for MusicTime {
     }
 }
 
-impl PartialOrd for MusicTime {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let other_time_sum = other.bar * 100 + other.beat as u16 * 10 + other.beat_interval as u16;
-        let self_time_sum = self.bar * 100 + self.beat as u16 * 10 + self.beat_interval as u16;
-        self_time_sum.partial_cmp(&other_time_sum)
-    }
-}
-
 impl Default for MusicTime {
     /// Default is `MusicTime::new(1,1,1)`
     fn default() -> MusicTime {
@@ -143,6 +831,221 @@ impl Default for MusicTime {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A relative amount of music time, counted in beat intervals within a time signature.
+/// Add it to or subtract it from a `MusicTime` to move it later or earlier, _e.g._ to
+/// transpose a list of events. Unlike `MusicTime` it is not a position.
+pub struct MusicTimeDelta {
+    intervals: i64,
+    time_signature: TimeSignature,
+}
+
+impl MusicTimeDelta {
+    /// Create a new `MusicTimeDelta`.
+    ///
+    /// # Arguments
+    /// * `intervals` - The number of beat intervals, negative to move earlier.
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{
+    ///     music_time::{MusicTime, MusicTimeDelta},
+    ///     time_signature::TimeSignature,
+    /// };
+    /// let offset = MusicTimeDelta::new(8, TimeSignature::new(4, 4));
+    /// assert_eq!(MusicTime::new(1, 4, 1) + offset, MusicTime::new(2, 1, 1));
+    /// assert_eq!(MusicTime::new(2, 1, 1) - offset, MusicTime::new(1, 4, 1));
+    /// ```
+    pub fn new(intervals: i64, time_signature: TimeSignature) -> Self {
+        MusicTimeDelta {
+            intervals,
+            time_signature,
+        }
+    }
+
+    /// Get the number of beat intervals.
+    pub fn get_intervals(&self) -> i64 {
+        self.intervals
+    }
+
+    /// Get the time signature the beat intervals are counted within.
+    pub fn get_time_signature(&self) -> &TimeSignature {
+        &self.time_signature
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A section of music from `start` up to but not including `end`, _e.g._ a verse or
+/// chorus. The music times are compared with the field-wise ordering of `MusicTime`.
+pub struct MusicRange {
+    start: MusicTime,
+    end: MusicTime,
+}
+
+impl MusicRange {
+    /// Create a new `MusicRange`. It is empty if `end` is not after `start`.
+    ///
+    /// # Arguments
+    /// * `start` - The first music time of the range.
+    /// * `end` - The music time the range ends before.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::music_time::{MusicRange, MusicTime};
+    /// let verse = MusicRange::new(MusicTime::new(1, 1, 1), MusicTime::new(9, 1, 1));
+    /// assert!(verse.contains(&MusicTime::new(8, 4, 8)));
+    /// assert!(!verse.contains(&MusicTime::new(9, 1, 1)));
+    /// ```
+    pub fn new(start: MusicTime, end: MusicTime) -> Self {
+        MusicRange { start, end }
+    }
+
+    /// Get the first music time of the range.
+    pub fn get_start(&self) -> &MusicTime {
+        &self.start
+    }
+
+    /// Get the music time the range ends before.
+    pub fn get_end(&self) -> &MusicTime {
+        &self.end
+    }
+
+    /// Returns `true` if the range has no music times.
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// Returns `true` if `time` is at or after the start and before the end.
+    ///
+    /// # Arguments
+    /// * `time` - The music time to check.
+    pub fn contains(&self, time: &MusicTime) -> bool {
+        &self.start <= time && time < &self.end
+    }
+
+    /// Returns `true` if this range and `other` share any music time. Ranges that only
+    /// touch, where one ends at the start of the other, do not overlap.
+    ///
+    /// # Arguments
+    /// * `other` - The range to check against.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::music_time::{MusicRange, MusicTime};
+    /// let verse = MusicRange::new(MusicTime::new(1, 1, 1), MusicTime::new(9, 1, 1));
+    /// let chorus = MusicRange::new(MusicTime::new(9, 1, 1), MusicTime::new(17, 1, 1));
+    /// let fill = MusicRange::new(MusicTime::new(8, 3, 1), MusicTime::new(9, 2, 1));
+    /// assert!(!verse.overlaps(&chorus));
+    /// assert!(fill.overlaps(&verse) && fill.overlaps(&chorus));
+    /// ```
+    pub fn overlaps(&self, other: &MusicRange) -> bool {
+        !self.is_empty() && !other.is_empty() && self.start < other.end && other.start < self.end
+    }
+
+    /// Gets the number of beat intervals in the range within a time signature, `0` if it
+    /// is empty.
+    ///
+    /// # Arguments
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{
+    ///     music_time::{MusicRange, MusicTime},
+    ///     time_signature::TimeSignature,
+    /// };
+    /// let bar = MusicRange::new(MusicTime::new(2, 1, 1), MusicTime::new(3, 1, 1));
+    /// assert_eq!(bar.length_intervals(&TimeSignature::new(4, 4)), 32);
+    /// ```
+    pub fn length_intervals(&self, time_signature: &TimeSignature) -> u64 {
+        let length = self.end.to_beat_interval_index(time_signature)
+            - self.start.to_beat_interval_index(time_signature);
+        length.max(0) as u64
+    }
+
+    /// Gets the bpm the range lasts `duration` at, the reverse of
+    /// `music_timer::duration_between`, _e.g._ for matching the tempo of a sample loop. The
+    /// bpm is `0.0` for an empty range or a zero duration.
+    ///
+    /// # Arguments
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    /// * `duration` - The duration of the range.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{
+    ///     music_time::{MusicRange, MusicTime},
+    ///     time_signature::TimeSignature,
+    /// };
+    /// use std::time::Duration;
+    /// let bar = MusicRange::new(MusicTime::new(2, 1, 1), MusicTime::new(3, 1, 1));
+    /// let bpm = bar.bpm_for_duration(&TimeSignature::new(4, 4), Duration::from_secs(2));
+    /// assert_eq!(bpm, 120.0);
+    /// ```
+    pub fn bpm_for_duration(&self, time_signature: &TimeSignature, duration: Duration) -> f32 {
+        let beat_intervals = self.length_intervals(time_signature);
+        let seconds = duration.as_secs_f64();
+        if beat_intervals == 0 || seconds <= 0.0 {
+            return 0.0;
+        }
+        let beats = beat_intervals as f64 / f64::from(DEFAULT_INTERVAL_RESOLUTION);
+        (beats * 60.0 / seconds) as f32
+    }
+
+    /// Stretches or compresses events in this range to fit the range `to`, _e.g._ to fit a
+    /// 4 bar phrase into 3 bars. Each event is scaled by its number of beat intervals from
+    /// the start of this range and rounded to the nearest beat interval, halfway rounding
+    /// later. The events are returned as they are if this range is empty.
+    ///
+    /// # Arguments
+    /// * `events` - The music times of the events.
+    /// * `to` - The range to fit the events to.
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{
+    ///     music_time::{MusicRange, MusicTime},
+    ///     time_signature::TimeSignature,
+    /// };
+    /// let four_bars = MusicRange::new(MusicTime::new(1, 1, 1), MusicTime::new(5, 1, 1));
+    /// let three_bars = MusicRange::new(MusicTime::new(1, 1, 1), MusicTime::new(4, 1, 1));
+    /// let events = [MusicTime::new(1, 1, 1), MusicTime::new(3, 1, 1), MusicTime::new(4, 3, 1)];
+    /// let fitted = four_bars.rescale_events(&events, &three_bars, &TimeSignature::new(4, 4));
+    /// assert_eq!(
+    ///     fitted,
+    ///     vec![MusicTime::new(1, 1, 1), MusicTime::new(2, 3, 1), MusicTime::new(3, 3, 5)]
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn rescale_events(
+        &self,
+        events: &[MusicTime],
+        to: &MusicRange,
+        time_signature: &TimeSignature,
+    ) -> Vec<MusicTime> {
+        let from_intervals = i128::from(self.length_intervals(time_signature));
+        let to_intervals = i128::from(to.length_intervals(time_signature));
+        if from_intervals == 0 {
+            return events.to_vec();
+        }
+        let from_start = i128::from(self.start.to_beat_interval_index(time_signature));
+        let to_start = i128::from(to.start.to_beat_interval_index(time_signature));
+        events
+            .iter()
+            .map(|event| {
+                let offset = i128::from(event.to_beat_interval_index(time_signature)) - from_start;
+                let scaled =
+                    (2 * offset * to_intervals + from_intervals).div_euclid(2 * from_intervals);
+                let index = (to_start + scaled).clamp(i128::from(i64::MIN), i128::from(i64::MAX));
+                MusicTime::from_beat_interval_index(index as i64, time_signature)
+            })
+            .collect()
+    }
+}
+
+#[allow(clippy::bool_assert_comparison, clippy::unnecessary_sort_by)]
 mod tests {
     #[test]
     fn test_order() {
@@ -164,6 +1067,40 @@ mod tests {
         assert_eq!(MusicTime::new(1, 1, 2) > MusicTime::new(1, 1, 1), true);
     }
 
+    #[test]
+    fn test_order_fields() {
+        use crate::music_time::MusicTime;
+
+        // Ordered field by field, like Ord
+        assert!(MusicTime::new(1, 11, 1) < MusicTime::new(2, 1, 1));
+        assert!(MusicTime::new(700, 1, 1) > MusicTime::new(699, 4, 8));
+        assert_eq!(
+            MusicTime::new(1, 11, 1).partial_cmp(&MusicTime::new(2, 1, 1)),
+            Some(MusicTime::new(1, 11, 1).cmp(&MusicTime::new(2, 1, 1)))
+        );
+    }
+
+    #[test]
+    fn test_cmp_in() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+        use std::cmp::Ordering;
+        let time_signature = TimeSignature::new(3, 4);
+
+        let a = MusicTime::new(1, 5, 1);
+        let b = MusicTime::new(2, 1, 1);
+        assert_eq!(a.cmp(&b), Ordering::Less);
+        assert_eq!(a.cmp_in(&b, &time_signature), Ordering::Greater);
+
+        let a = MusicTime::new(1, 1, 9);
+        let b = MusicTime::new(1, 2, 1);
+        assert_eq!(a.cmp(&b), Ordering::Less);
+        assert_eq!(a.cmp_in(&b, &time_signature), Ordering::Equal);
+
+        let a = MusicTime::new(1, 3, 8);
+        let b = MusicTime::new(2, 1, 1);
+        assert_eq!(a.cmp(&b), a.cmp_in(&b, &time_signature));
+    }
+
     #[test]
     fn test_equality() {
         use crate::music_time::MusicTime;
@@ -223,6 +1160,24 @@ mod tests {
         assert_eq!(a.get_bar() == 5 && a.get_beat() == 1, true);
     }
 
+    #[test]
+    fn test_advance_beat_overflow() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+        let time_signature = TimeSignature::new(3, 4);
+
+        let mut a = MusicTime::new(u16::MAX, 2, 1);
+        a.saturating_advance_beat(&time_signature);
+        assert_eq!(a, MusicTime::new(u16::MAX, 3, 1));
+        a.saturating_advance_beat(&time_signature);
+        assert_eq!(a, MusicTime::new(u16::MAX, 3, 1));
+
+        let mut a = MusicTime::new(u16::MAX, 2, 1);
+        a.advance_beat(&time_signature);
+        assert_eq!(a, MusicTime::new(u16::MAX, 3, 1));
+        a.advance_beat(&time_signature);
+        assert_eq!(a, MusicTime::new(0, 1, 1));
+    }
+
     #[test]
     fn test_advance_beat_interval() {
         use crate::{music_time::MusicTime, time_signature::TimeSignature};
@@ -242,6 +1197,210 @@ mod tests {
         assert_eq!(a, MusicTime::new(1, 2, 1));
     }
 
+    #[test]
+    fn test_pre_roll() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+        let time_signature = TimeSignature::new(3, 4);
+
+        let mut a = MusicTime::new(0, 3, 1);
+        a.advance_beat(&time_signature);
+        assert_eq!(a, MusicTime::new(1, 1, 1));
+        a.retreat_beat(&time_signature);
+        assert_eq!(a, MusicTime::new(0, 3, 1));
+
+        let mut a = MusicTime::new(0, 3, 8);
+        a.advance_beat_interval(&time_signature);
+        assert_eq!(a, MusicTime::new(1, 1, 1));
+        a.retreat_beat_interval(&time_signature);
+        assert_eq!(a, MusicTime::new(0, 3, 8));
+        assert_eq!(a.to_beat_interval_index(&time_signature), -1);
+
+        // Nothing comes before the pre-roll bar
+        let mut a = MusicTime::new(0, 1, 1);
+        a.retreat_beat_interval(&time_signature);
+        assert_eq!(a, MusicTime::new(0, 1, 1));
+        a.retreat_beat(&time_signature);
+        assert_eq!(a, MusicTime::new(0, 1, 1));
+    }
+
+    #[test]
+    fn test_within() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+        let time_signature = TimeSignature::new(4, 4);
+        let bar_end = MusicTime::new(1, 4, 8);
+        let bar_start = MusicTime::new(2, 1, 1);
+
+        assert!(bar_end.within(&bar_end, 0, &time_signature));
+        assert!(!bar_end.within(&bar_start, 0, &time_signature));
+        assert!(bar_end.within(&bar_start, 1, &time_signature));
+        assert!(bar_start.within(&bar_end, 1, &time_signature));
+        assert!(!MusicTime::new(1, 4, 7).within(&bar_start, 1, &time_signature));
+        assert!(!bar_end.within(&MusicTime::new(2, 1, 2), 1, &time_signature));
+    }
+
+    #[test]
+    fn test_floor() {
+        use crate::music_time::MusicTime;
+
+        let a = MusicTime::new(3, 2, 5);
+        assert_eq!(a.floor_to_bar(), MusicTime::new(3, 1, 1));
+        assert_eq!(a.floor_to_beat(), MusicTime::new(3, 2, 1));
+
+        let a = MusicTime::new(3, 1, 1);
+        assert_eq!(a.floor_to_bar(), a);
+        assert_eq!(a.floor_to_beat(), a);
+    }
+
+    #[test]
+    fn test_ceil() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+        let time_signature = TimeSignature::new(3, 4);
+
+        let a = MusicTime::new(3, 2, 5);
+        assert_eq!(a.ceil_to_bar(&time_signature), MusicTime::new(4, 1, 1));
+        assert_eq!(a.ceil_to_beat(&time_signature), MusicTime::new(3, 3, 1));
+
+        let a = MusicTime::new(3, 3, 2);
+        assert_eq!(a.ceil_to_bar(&time_signature), MusicTime::new(4, 1, 1));
+        assert_eq!(a.ceil_to_beat(&time_signature), MusicTime::new(4, 1, 1));
+
+        // On a boundary stays put
+        let a = MusicTime::new(3, 1, 1);
+        assert_eq!(a.ceil_to_bar(&time_signature), a);
+        assert_eq!(a.ceil_to_beat(&time_signature), a);
+
+        let a = MusicTime::new(3, 2, 1);
+        assert_eq!(a.ceil_to_bar(&time_signature), MusicTime::new(4, 1, 1));
+        assert_eq!(a.ceil_to_beat(&time_signature), a);
+    }
+
+    #[test]
+    fn test_ppqn() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+
+        for &ppqn in &[480, 960] {
+            for time_signature in &[TimeSignature::new(4, 4), TimeSignature::new(6, 8)] {
+                // A beat interval is an eighth of a beat, of the note value of the denominator
+                let interval_ticks =
+                    u64::from(ppqn) * 4 / (u64::from(time_signature.get_denominator()) * 8);
+                let mut time = MusicTime::default();
+                for ticks in 0..100 {
+                    assert_eq!(time.to_ppqn(time_signature, ppqn), ticks * interval_ticks);
+                    assert_eq!(
+                        MusicTime::from_ppqn(ticks * interval_ticks, time_signature, ppqn),
+                        time
+                    );
+                    time.advance_beat_interval(time_signature);
+                }
+            }
+
+            // A bar of 4/4 is 4 quarter notes and a bar of 6/8 is 3
+            let bar = MusicTime::new(2, 1, 1);
+            assert_eq!(
+                bar.to_ppqn(&TimeSignature::new(4, 4), ppqn),
+                4 * u64::from(ppqn)
+            );
+            assert_eq!(
+                bar.to_ppqn(&TimeSignature::new(6, 8), ppqn),
+                3 * u64::from(ppqn)
+            );
+        }
+
+        let time_signature = TimeSignature::new(4, 4);
+        assert_eq!(MusicTime::new(0, 4, 8).to_ppqn(&time_signature, 480), 0);
+        assert_eq!(
+            MusicTime::from_ppqn(59, &time_signature, 480),
+            MusicTime::new(1, 1, 1)
+        );
+    }
+
+    #[test]
+    fn test_quantize_to_grid() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+        let time_signature = TimeSignature::new(4, 4);
+
+        // One beat
+        let beat_grid = 8;
+        let a = MusicTime::new(1, 2, 4);
+        assert_eq!(
+            a.quantize_to_grid(beat_grid, &time_signature),
+            MusicTime::new(1, 2, 1)
+        );
+        let a = MusicTime::new(1, 2, 5);
+        assert_eq!(
+            a.quantize_to_grid(beat_grid, &time_signature),
+            MusicTime::new(1, 3, 1)
+        );
+        let a = MusicTime::new(1, 4, 8);
+        assert_eq!(
+            a.quantize_to_grid(beat_grid, &time_signature),
+            MusicTime::new(2, 1, 1)
+        );
+
+        // One bar
+        let bar_grid = 32;
+        let a = MusicTime::new(3, 2, 8);
+        assert_eq!(
+            a.quantize_to_grid(bar_grid, &time_signature),
+            MusicTime::new(3, 1, 1)
+        );
+        let a = MusicTime::new(3, 3, 1);
+        assert_eq!(
+            a.quantize_to_grid(bar_grid, &time_signature),
+            MusicTime::new(4, 1, 1)
+        );
+        let a = MusicTime::new(0, 3, 2);
+        assert_eq!(
+            a.quantize_to_grid(bar_grid, &time_signature),
+            MusicTime::new(1, 1, 1)
+        );
+
+        let a = MusicTime::new(2, 3, 7);
+        assert_eq!(a.quantize_to_grid(0, &time_signature), a);
+    }
+
+    #[test]
+    fn test_from_beat_interval_index() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+        let time_signature = TimeSignature::new(3, 4);
+
+        for &time in &[
+            MusicTime::new(0, 1, 1),
+            MusicTime::new(0, 3, 8),
+            MusicTime::new(1, 1, 1),
+            MusicTime::new(4, 2, 5),
+        ] {
+            let index = time.to_beat_interval_index(&time_signature);
+            assert_eq!(
+                MusicTime::from_beat_interval_index(index, &time_signature),
+                time
+            );
+        }
+        assert_eq!(
+            MusicTime::from_beat_interval_index(-100, &time_signature),
+            MusicTime::new(0, 1, 1)
+        );
+    }
+
+    #[test]
+    fn test_offset() {
+        use crate::{
+            music_time::{MusicTime, MusicTimeDelta},
+            time_signature::TimeSignature,
+        };
+        let time_signature = TimeSignature::new(3, 4);
+
+        let a = MusicTime::new(1, 3, 8);
+        assert_eq!(a.offset_by(10, &time_signature), MusicTime::new(2, 2, 2));
+        let a = MusicTime::new(4, 1, 1);
+        assert_eq!(a.offset_by(10, &time_signature), MusicTime::new(4, 2, 3));
+
+        let offset = MusicTimeDelta::new(10, time_signature);
+        assert_eq!(MusicTime::new(1, 3, 8) + offset, MusicTime::new(2, 2, 2));
+        assert_eq!(MusicTime::new(2, 2, 2) - offset, MusicTime::new(1, 3, 8));
+        assert_eq!(MusicTime::new(1, 1, 1) - offset, MusicTime::new(0, 2, 7));
+    }
+
     #[test]
     fn test_event_sort() {
         use crate::music_time::MusicTime;
@@ -267,4 +1426,374 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_exact_offset() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+        let time_signature = TimeSignature::new(4, 4);
+
+        // 3 beats at 100bpm is 9/5 seconds
+        let time = MusicTime::new(1, 4, 1);
+        assert_eq!(time.exact_offset(&time_signature, 100, 1), (9, 5));
+        assert_eq!(time.exact_offset(&time_signature, 200, 2), (9, 5));
+
+        // 3 beats at 100.5bpm is 360/201 seconds
+        assert_eq!(time.exact_offset(&time_signature, 201, 2), (120, 67));
+
+        assert_eq!(
+            MusicTime::new(1, 1, 1).exact_offset(&time_signature, 100, 1),
+            (0, 1)
+        );
+        assert_eq!(
+            MusicTime::new(0, 4, 1).exact_offset(&time_signature, 100, 1),
+            (0, 1)
+        );
+        assert_eq!(time.exact_offset(&time_signature, 0, 1), (0, 1));
+
+        // An hour at 120bpm
+        let time = MusicTime::new(1801, 1, 1);
+        assert_eq!(time.exact_offset(&time_signature, 120, 1), (3600, 1));
+    }
+
+    #[test]
+    fn test_setters() {
+        use crate::{
+            music_time::{MusicTime, MusicTimeError},
+            time_signature::TimeSignature,
+        };
+        let time_signature = TimeSignature::new(4, 4);
+        let mut time = MusicTime::new(1, 1, 1);
+
+        time.set_bar(3);
+        assert_eq!(time.set_beat(2, &time_signature), Ok(()));
+        assert_eq!(time.set_beat_interval(8, &time_signature), Ok(()));
+        assert_eq!(time, MusicTime::new(3, 2, 8));
+
+        assert_eq!(
+            time.set_beat(0, &time_signature),
+            Err(MusicTimeError::BeatOutOfRange)
+        );
+        assert_eq!(
+            time.set_beat(5, &time_signature),
+            Err(MusicTimeError::BeatOutOfRange)
+        );
+        assert_eq!(
+            time.set_beat(9, &time_signature),
+            Err(MusicTimeError::BeatOutOfRange)
+        );
+        assert_eq!(
+            time.set_beat_interval(0, &time_signature),
+            Err(MusicTimeError::BeatIntervalOutOfRange)
+        );
+        assert_eq!(
+            time.set_beat_interval(9, &time_signature),
+            Err(MusicTimeError::BeatIntervalOutOfRange)
+        );
+        assert_eq!(
+            time.set_beat_interval(9, &TimeSignature::new(4, 16)),
+            Err(MusicTimeError::BeatIntervalOutOfRange)
+        );
+
+        // Rejected values leave the music time as-is
+        assert_eq!(time, MusicTime::new(3, 2, 8));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_beats_in_bar() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+
+        let beats = MusicTime::beats_in_bar(1, &TimeSignature::new(4, 4));
+        assert_eq!(
+            beats,
+            vec![
+                MusicTime::new(1, 1, 1),
+                MusicTime::new(1, 2, 1),
+                MusicTime::new(1, 3, 1),
+                MusicTime::new(1, 4, 1),
+            ]
+        );
+        assert_eq!(beats.capacity(), 4);
+
+        let beats = MusicTime::beats_in_bar(5, &TimeSignature::new(7, 8));
+        assert_eq!(beats.len(), 7);
+        assert_eq!(beats.first(), Some(&MusicTime::new(5, 1, 1)));
+        assert_eq!(beats.last(), Some(&MusicTime::new(5, 7, 1)));
+    }
+
+    #[test]
+    fn test_bytes() {
+        use crate::music_time::MusicTime;
+
+        let times = [
+            MusicTime::default(),
+            MusicTime::new(0, 4, 8),
+            MusicTime::new(258, 3, 7),
+            MusicTime::new(u16::MAX, u8::MAX, u8::MAX),
+        ];
+        for time in times.iter() {
+            assert_eq!(&MusicTime::from_bytes(time.to_bytes()), time);
+        }
+
+        assert_eq!(MusicTime::new(1, 1, 1).to_bytes(), [1, 0, 1, 1]);
+        assert_eq!(MusicTime::new(0x1234, 2, 5).to_bytes(), [0x34, 0x12, 2, 5]);
+    }
+
+    #[test]
+    fn test_float_bars() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+
+        let time_signature = TimeSignature::new(4, 4);
+        assert_eq!(MusicTime::new(1, 1, 1).as_float_bars(&time_signature), 1.0);
+        assert_eq!(MusicTime::new(2, 3, 1).as_float_bars(&time_signature), 2.5);
+        assert_eq!(
+            MusicTime::new(2, 1, 5).as_float_bars(&time_signature),
+            2.125
+        );
+        assert_eq!(MusicTime::new(0, 3, 1).as_float_bars(&time_signature), 0.5);
+
+        let times = [
+            MusicTime::new(0, 3, 1),
+            MusicTime::new(1, 1, 1),
+            MusicTime::new(2, 3, 1),
+            MusicTime::new(7, 4, 8),
+        ];
+        for time in times.iter() {
+            let bars = time.as_float_bars(&time_signature);
+            assert_eq!(&MusicTime::from_float_bars(bars, &time_signature), time);
+        }
+
+        // Rounded to the nearest beat interval
+        assert_eq!(
+            MusicTime::from_float_bars(2.51, &time_signature),
+            MusicTime::new(2, 3, 1)
+        );
+
+        let time_signature = TimeSignature::new(3, 4);
+        assert_eq!(
+            MusicTime::from_float_bars(3.0 + 2.0 / 3.0, &time_signature),
+            MusicTime::new(3, 3, 1)
+        );
+    }
+
+    #[test]
+    fn test_checked_new() {
+        use crate::music_time::MusicTime;
+
+        assert_eq!(MusicTime::checked_new(1, 1, 1), Some(MusicTime::default()));
+        assert_eq!(
+            MusicTime::checked_new(0, 4, 8),
+            Some(MusicTime::new(0, 4, 8))
+        );
+        assert_eq!(MusicTime::checked_new(1, 0, 1), None);
+        assert_eq!(MusicTime::checked_new(1, 1, 0), None);
+        assert_eq!(MusicTime::checked_new(1, 1, 9), None);
+        assert_eq!(MusicTime::checked_new(1, 0, 0), None);
+    }
+
+    #[test]
+    fn test_add_bars() {
+        use crate::music_time::MusicTime;
+
+        let time = MusicTime::new(0, 4, 8);
+        assert_eq!(time.add_bars(1), MusicTime::new(1, 4, 8));
+        assert_eq!(time.add_bars(0), time);
+        assert_eq!(time.checked_add_bars(4), Some(MusicTime::new(4, 4, 8)));
+
+        // Near overflow
+        let time = MusicTime::new(u16::MAX - 2, 2, 3);
+        assert_eq!(time.add_bars(2), MusicTime::new(u16::MAX, 2, 3));
+        assert_eq!(time.add_bars(3), MusicTime::new(u16::MAX, 2, 3));
+        assert_eq!(time.add_bars(u16::MAX), MusicTime::new(u16::MAX, 2, 3));
+        assert_eq!(
+            time.checked_add_bars(2),
+            Some(MusicTime::new(u16::MAX, 2, 3))
+        );
+        assert_eq!(time.checked_add_bars(3), None);
+    }
+
+    #[test]
+    fn test_range_contains() {
+        use crate::music_time::{MusicRange, MusicTime};
+
+        let range = MusicRange::new(MusicTime::new(2, 1, 1), MusicTime::new(4, 1, 1));
+        assert_eq!(range.get_start(), &MusicTime::new(2, 1, 1));
+        assert_eq!(range.get_end(), &MusicTime::new(4, 1, 1));
+        assert!(!range.is_empty());
+
+        // The start is included and the end is not
+        assert!(range.contains(&MusicTime::new(2, 1, 1)));
+        assert!(range.contains(&MusicTime::new(3, 4, 8)));
+        assert!(!range.contains(&MusicTime::new(4, 1, 1)));
+        assert!(!range.contains(&MusicTime::new(1, 4, 8)));
+
+        let empty = MusicRange::new(MusicTime::new(2, 1, 1), MusicTime::new(2, 1, 1));
+        assert!(empty.is_empty());
+        assert!(!empty.contains(&MusicTime::new(2, 1, 1)));
+    }
+
+    #[test]
+    fn test_range_overlaps() {
+        use crate::{
+            music_time::{MusicRange, MusicTime},
+            time_signature::TimeSignature,
+        };
+
+        let range = |start: u16, end: u16| {
+            MusicRange::new(MusicTime::new(start, 1, 1), MusicTime::new(end, 1, 1))
+        };
+        assert!(range(1, 5).overlaps(&range(4, 8)));
+        assert!(range(4, 8).overlaps(&range(1, 5)));
+        assert!(range(1, 9).overlaps(&range(3, 4)));
+        assert!(!range(1, 5).overlaps(&range(5, 9)));
+        assert!(!range(5, 9).overlaps(&range(1, 5)));
+        assert!(!range(1, 5).overlaps(&range(3, 3)));
+        assert!(!range(6, 2).overlaps(&range(1, 9)));
+
+        let time_signature = TimeSignature::new(3, 4);
+        assert_eq!(range(1, 5).length_intervals(&time_signature), 4 * 24);
+        assert_eq!(range(6, 2).length_intervals(&time_signature), 0);
+    }
+
+    #[test]
+    fn test_from_beat_interval_index_bounds() {
+        use crate::{music_time::MusicTime, time_signature::TimeSignature};
+        let time_signature = TimeSignature::new(3, 4);
+
+        // Saturates at the last beat interval of the last bar
+        let last = MusicTime::new(u16::MAX, 3, 8);
+        assert_eq!(
+            MusicTime::from_beat_interval_index(i64::MAX, &time_signature),
+            last
+        );
+        assert_eq!(
+            MusicTime::new(1, 1, 1).offset_by(u64::MAX, &time_signature),
+            last
+        );
+        assert_eq!(
+            MusicTime::from_beat_interval_index(
+                last.to_beat_interval_index(&time_signature) + 1,
+                &time_signature
+            ),
+            last
+        );
+
+        // No beats per bar
+        assert_eq!(
+            MusicTime::from_beat_interval_index(25, &TimeSignature::new(0, 4)),
+            MusicTime::default()
+        );
+    }
+
+    #[test]
+    fn test_range_bpm_for_duration() {
+        use crate::{
+            music_time::{MusicRange, MusicTime},
+            time_signature::TimeSignature,
+        };
+        use core::time::Duration;
+
+        // 2 bars of 4/4 in 4 seconds
+        let time_signature = TimeSignature::new(4, 4);
+        let start = MusicTime::new(3, 1, 1);
+        let range = MusicRange::new(start, MusicTime::new(5, 1, 1));
+        assert_eq!(
+            range.bpm_for_duration(&time_signature, Duration::from_secs(4)),
+            120.0
+        );
+
+        // The reverse of duration_between
+        let duration =
+            crate::duration_between(range.get_start(), range.get_end(), &time_signature, 97.0);
+        let bpm = range.bpm_for_duration(&time_signature, duration);
+        assert!((bpm - 97.0).abs() < 0.001);
+
+        let time_signature = TimeSignature::new(6, 8);
+        let range = MusicRange::new(MusicTime::new(1, 1, 1), MusicTime::new(2, 4, 1));
+        assert_eq!(
+            range.bpm_for_duration(&time_signature, Duration::from_secs(3)),
+            180.0
+        );
+
+        assert_eq!(
+            MusicRange::new(start, start).bpm_for_duration(&time_signature, Duration::from_secs(4)),
+            0.0
+        );
+        assert_eq!(
+            range.bpm_for_duration(&time_signature, Duration::default()),
+            0.0
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_range_rescale_events() {
+        use crate::{
+            music_time::{MusicRange, MusicTime},
+            time_signature::TimeSignature,
+        };
+
+        let time_signature = TimeSignature::new(4, 4);
+        let four_bars = MusicRange::new(MusicTime::new(1, 1, 1), MusicTime::new(5, 1, 1));
+        let two_bars = MusicRange::new(MusicTime::new(1, 1, 1), MusicTime::new(3, 1, 1));
+        let events = vec![
+            MusicTime::new(1, 1, 1),
+            MusicTime::new(1, 3, 1),
+            MusicTime::new(2, 1, 1),
+            MusicTime::new(3, 2, 5),
+            MusicTime::new(4, 4, 8),
+        ];
+
+        // Compressing 4 bars into 2 halves the positions
+        let compressed = four_bars.rescale_events(&events, &two_bars, &time_signature);
+        assert_eq!(
+            compressed,
+            vec![
+                MusicTime::new(1, 1, 1),
+                MusicTime::new(1, 2, 1),
+                MusicTime::new(1, 3, 1),
+                MusicTime::new(2, 1, 7),
+                // Halfway rounds later
+                MusicTime::new(3, 1, 1),
+            ]
+        );
+        for (event, scaled) in events.iter().zip(compressed.iter()) {
+            let index = event.to_beat_interval_index(&time_signature);
+            assert_eq!(
+                scaled.to_beat_interval_index(&time_signature),
+                (index + 1) / 2
+            );
+        }
+
+        // Stretching back restores the events that were on the grid
+        let stretched = two_bars.rescale_events(&compressed, &four_bars, &time_signature);
+        assert_eq!(stretched[..4], events[..4]);
+
+        // Events are scaled from the start of the range into the start of the other
+        let verse = MusicRange::new(MusicTime::new(5, 1, 1), MusicTime::new(9, 1, 1));
+        let chorus = MusicRange::new(MusicTime::new(9, 1, 1), MusicTime::new(11, 1, 1));
+        assert_eq!(
+            verse.rescale_events(
+                &[
+                    MusicTime::new(5, 1, 1),
+                    MusicTime::new(5, 3, 1),
+                    MusicTime::new(7, 1, 1)
+                ],
+                &chorus,
+                &time_signature
+            ),
+            vec![
+                MusicTime::new(9, 1, 1),
+                MusicTime::new(9, 2, 1),
+                MusicTime::new(10, 1, 1)
+            ]
+        );
+
+        // An empty range to scale from leaves the events as they are
+        let empty = MusicRange::new(MusicTime::new(1, 1, 1), MusicTime::new(1, 1, 1));
+        assert_eq!(
+            empty.rescale_events(&events, &two_bars, &time_signature),
+            events
+        );
+    }
 }
@@ -4,14 +4,56 @@
 //! Data structures that handles advancing music time within a time signature.
 //!
 
-use super::{music_time::MusicTime, time_signature::TimeSignature};
-use std::time::Duration;
+use super::{
+    music_time::MusicTime,
+    time_signature::{TimeSignature, DEFAULT_INTERVAL_RESOLUTION},
+};
+use core::time::Duration;
 
-#[derive(Debug)]
+/// Gets the time duration between beats, without a `MusicTimeCounter`.
+///
+/// # Arguments
+/// * `bpm` - Beats per minute.
+///
+/// # Example
+/// ```
+/// use music_timer::music_time_counter::beat_duration;
+/// use std::time::Duration;
+/// assert_eq!(beat_duration(120.0), Duration::from_millis(500));
+/// ```
+pub fn beat_duration(bpm: f32) -> Duration {
+    let seconds_per_beat = 60.0 / f64::from(bpm);
+    let beat_pulse_speed = seconds_per_beat * 1000000000.0;
+    Duration::from_nanos(beat_pulse_speed as u64)
+}
+
+/// Gets the time duration between beat intervals, a beat divided by the resolution,
+/// without a `MusicTimeCounter`.
+///
+/// # Arguments
+/// * `bpm` - Beats per minute.
+/// * `resolution` - The number of beat intervals in a beat, see
+///   `MusicTimeCounter::resolution`.
+///
+/// # Example
+/// ```
+/// use music_timer::music_time_counter::interval_duration;
+/// use std::time::Duration;
+/// assert_eq!(interval_duration(120.0, 8), Duration::from_micros(62500));
+/// ```
+pub fn interval_duration(bpm: f32, resolution: u8) -> Duration {
+    let seconds_per_beat_interval = (60.0 / f64::from(bpm)) / f64::from(resolution);
+    let beat_interval_pulse_speed = seconds_per_beat_interval * 1000000000.0;
+    Duration::from_nanos(beat_interval_pulse_speed as u64)
+}
+
+#[derive(Clone, Debug)]
 /// Data structure that handles advancing music time within a time signature.
 pub struct MusicTimeCounter {
     current_time: MusicTime,
     time_signature: TimeSignature,
+    resolution: u8,
+    is_free_running: bool,
 }
 
 impl MusicTimeCounter {
@@ -26,26 +68,186 @@ impl MusicTimeCounter {
     /// let timer = MusicTimeCounter::new(TimeSignature::new(3, 4));
     /// ```
     pub fn new(time_signature: TimeSignature) -> Self {
+        MusicTimeCounter::new_at(time_signature, MusicTime::new(1, 1, 1))
+    }
+
+    /// Create a new `MusicTimeCounter` with a `TimeSignature`, starting at `start` rather
+    /// than `MusicTime::new(1, 1, 1)`, _e.g._ to resume a saved session.
+    /// # Arguments
+    ///
+    /// * `time_signature` - The `TimeSignature` the `MusicTimeCounter` is constrained by.
+    /// * `start` - The current time the counter starts at.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{
+    ///     music_time::MusicTime, music_time_counter::MusicTimeCounter,
+    ///     time_signature::TimeSignature,
+    /// };
+    /// let timer = MusicTimeCounter::new_at(TimeSignature::new(3, 4), MusicTime::new(5, 2, 3));
+    /// assert_eq!(timer.current_time(), &MusicTime::new(5, 2, 3));
+    /// ```
+    pub fn new_at(time_signature: TimeSignature, start: MusicTime) -> Self {
         MusicTimeCounter {
-            current_time: MusicTime::new(1, 1, 1),
+            current_time: start,
             time_signature,
+            resolution: DEFAULT_INTERVAL_RESOLUTION,
+            is_free_running: false,
         }
     }
 
+    /// Divide each beat into `resolution` beat intervals rather than
+    /// `DEFAULT_INTERVAL_RESOLUTION`, _e.g._ `3`, `6` or `12` for tuplets. A resolution of
+    /// `0` is treated as `1`.
+    ///
+    /// # Arguments
+    /// * `resolution` - The number of beat intervals in a beat.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time_counter::MusicTimeCounter};
+    /// let timer = MusicTimeCounter::new(TimeSignature::new(3, 4)).with_resolution(3);
+    /// assert_eq!(timer.resolution(), 3);
+    /// ```
+    pub fn with_resolution(mut self, resolution: u8) -> Self {
+        self.resolution = resolution.max(1);
+        self
+    }
+
+    /// Gets the number of beat intervals in a beat. Unless set with `with_resolution` it
+    /// is `DEFAULT_INTERVAL_RESOLUTION` for every time signature, the resolution `MusicTime`
+    /// counts in, so the positions of the counter can be used with the `MusicTime` APIs.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time_counter::MusicTimeCounter};
+    /// assert_eq!(MusicTimeCounter::new(TimeSignature::new(4, 4)).resolution(), 8);
+    /// assert_eq!(MusicTimeCounter::new(TimeSignature::new(7, 16)).resolution(), 8);
+    /// ```
+    pub fn resolution(&self) -> u8 {
+        self.resolution
+    }
+
+    /// Create a counter for a cross rhythm against this counter, with `ratio_num` beat
+    /// intervals for every `ratio_den` beat intervals of this counter, _e.g._ `3` against
+    /// `4`. Both counters share the time signature, so at the same bpm their beats line up in
+    /// real time. The cross rhythm starts at the current time of this counter, with the
+    /// beat interval rounded down. The resolution is rounded to the nearest whole beat
+    /// interval, so `ratio_den` should divide this counter's resolution times `ratio_num`.
+    ///
+    /// # Arguments
+    /// * `ratio_num` - The number of beat intervals of the cross rhythm.
+    /// * `ratio_den` - The number of beat intervals of this counter they span.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time_counter::MusicTimeCounter};
+    /// let timer = MusicTimeCounter::new(TimeSignature::new(4, 4));
+    /// // 3 against 4
+    /// let cross = timer.cross_rhythm(3, 4);
+    /// assert_eq!(cross.resolution(), 6);
+    /// ```
+    pub fn cross_rhythm(&self, ratio_num: u8, ratio_den: u8) -> MusicTimeCounter {
+        let ratio_den = u32::from(ratio_den.max(1));
+        let scaled_resolution = u32::from(self.resolution()) * u32::from(ratio_num);
+        let resolution = (scaled_resolution + ratio_den / 2) / ratio_den;
+        let resolution = resolution.clamp(1, u32::from(u8::MAX));
+        let beat_interval = u32::from(self.current_time.get_beat_interval().max(1)) - 1;
+        let beat_interval = (beat_interval * resolution / u32::from(self.resolution())) + 1;
+
+        let mut counter =
+            MusicTimeCounter::new(self.time_signature).with_resolution(resolution as u8);
+        counter.set_free_running(self.is_free_running);
+        counter.set_current_time(MusicTime::new(
+            self.current_time.get_bar(),
+            self.current_time.get_beat(),
+            beat_interval as u8,
+        ));
+        counter
+    }
+
     /// Gets the current time of the counter.
     pub fn current_time(&self) -> &MusicTime {
         &self.current_time
     }
 
+    /// Gets the number of beat intervals to advance from the current time to the start of
+    /// the next bar.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{
+    ///     music_time::MusicTime, music_time_counter::MusicTimeCounter,
+    ///     time_signature::TimeSignature,
+    /// };
+    /// let mut timer = MusicTimeCounter::new(TimeSignature::new(4, 4));
+    /// timer.set_current_time(MusicTime::new(1, 4, 7));
+    /// assert_eq!(timer.remaining_intervals_in_bar(), 2);
+    /// ```
+    pub fn remaining_intervals_in_bar(&self) -> u64 {
+        let resolution = u64::from(self.resolution());
+        let numerator = u64::from(self.time_signature.get_numerator());
+        let beat = u64::from(self.current_time.get_beat().max(1));
+        let beat_interval = u64::from(self.current_time.get_beat_interval().max(1));
+        let beats_remaining = numerator.saturating_sub(beat);
+        beats_remaining * resolution + (resolution + 1).saturating_sub(beat_interval)
+    }
+
+    /// Count beats without ever wrapping into the next bar, as a single endless bar. The
+    /// beat of the current time keeps counting past the numerator of the time signature,
+    /// wrapping back to `1` after beat `u8::MAX`, and the bar stays as it is.
+    ///
+    /// # Arguments
+    /// * `is_free_running` - `true` to ignore the numerator, `false` to wrap into bars.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{
+    ///     music_time::MusicTime, music_time_counter::MusicTimeCounter,
+    ///     time_signature::TimeSignature,
+    /// };
+    /// let mut timer = MusicTimeCounter::new(TimeSignature::new(4, 4));
+    /// timer.set_free_running(true);
+    /// for _ in 0..4 {
+    ///     timer.advance_beat();
+    /// }
+    /// assert_eq!(timer.current_time(), &MusicTime::new(1, 5, 1));
+    /// ```
+    pub fn set_free_running(&mut self, is_free_running: bool) -> &mut Self {
+        self.is_free_running = is_free_running;
+        self
+    }
+
+    /// Returns `true` if the counter counts beats without wrapping into bars.
+    pub fn is_free_running(&self) -> bool {
+        self.is_free_running
+    }
+
     /// Advance this counter by 1 beat.
     pub fn advance_beat(&mut self) {
-        self.current_time.advance_beat(&self.time_signature);
+        if self.is_free_running {
+            let beat_interval = self.current_time.get_beat_interval();
+            self.current_time = self.next_free_running_beat(beat_interval);
+        } else {
+            self.current_time.advance_beat(&self.time_signature);
+        }
     }
 
     /// Advance this counter by 1 beat interval.
     pub fn advance_beat_interval(&mut self) {
-        self.current_time
-            .advance_beat_interval(&self.time_signature);
+        let resolution = self.resolution();
+        let beat_interval = self.current_time.get_beat_interval();
+        if self.is_free_running && beat_interval >= resolution {
+            self.current_time = self.next_free_running_beat(1);
+        } else {
+            self.current_time
+                .advance_beat_interval_with_resolution(&self.time_signature, resolution);
+        }
+    }
+
+    fn next_free_running_beat(&self, beat_interval: u8) -> MusicTime {
+        let beat = self.current_time.get_beat().checked_add(1).unwrap_or(1);
+        MusicTime::new(self.current_time.get_bar(), beat, beat_interval)
     }
 
     /// Gets the time duration between beats.
@@ -53,20 +255,27 @@ impl MusicTimeCounter {
     ///
     /// * `bpm` - Beats per minute
     pub fn beat_target_frames(&self, bpm: f32) -> Duration {
-        let seconds_per_beat = 60.0 / bpm;
-        let beat_pulse_speed = seconds_per_beat * 1000000000.0;
-        Duration::from_nanos(beat_pulse_speed as u64)
+        beat_duration(bpm)
     }
 
-    /// Gets the time duration between beat intervals.
+    /// Gets the time duration between beat intervals, a beat divided by the resolution.
     /// # Arguments
     ///
     /// * `bpm` - Beats per minute.
     pub fn beat_interval_target_frames(&self, bpm: f32) -> Duration {
-        const INTERVAL_RESOLUTION: f32 = 16.0 / 2.0;
-        let seconds_per_beat_interval = (60.0 / bpm) / INTERVAL_RESOLUTION;
-        let beat_interval_pulse_speed = seconds_per_beat_interval * 1000000000.0;
-        Duration::from_nanos(beat_interval_pulse_speed as u64)
+        interval_duration(bpm, self.resolution())
+    }
+
+    /// Gets the time duration between MIDI clock pulses, 24 per quarter note.
+    /// # Arguments
+    ///
+    /// * `bpm` - Beats per minute, where a beat is the denominator of the time signature.
+    pub fn midi_clock_target_frames(&self, bpm: f32) -> Duration {
+        const PULSES_PER_QUARTER_NOTE: f64 = 24.0;
+        let beats_per_quarter_note = f64::from(self.time_signature.get_denominator()) / 4.0;
+        let seconds_per_quarter_note = (60.0 / f64::from(bpm)) * beats_per_quarter_note;
+        let seconds_per_pulse = seconds_per_quarter_note / PULSES_PER_QUARTER_NOTE;
+        Duration::from_nanos((seconds_per_pulse * 1000000000.0) as u64)
     }
 
     /// Set the current music time of the counter.
@@ -77,6 +286,49 @@ impl MusicTimeCounter {
         self.current_time = current_time;
         self
     }
+
+    /// Gets the time signature the counter is constrained by.
+    pub fn time_signature(&self) -> &TimeSignature {
+        &self.time_signature
+    }
+
+    /// Set the time signature of the counter. The current time is left as-is and
+    /// future advances will wrap against the new time signature.
+    ///
+    /// # Arguments
+    /// * `time_signature` - The new time signature to constrain the counter by.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{time_signature::TimeSignature, music_time_counter::MusicTimeCounter};
+    /// let mut timer = MusicTimeCounter::new(TimeSignature::new(4, 4));
+    /// timer.set_time_signature(TimeSignature::new(7, 8));
+    /// assert_eq!(timer.time_signature(), &TimeSignature::new(7, 8));
+    /// ```
+    pub fn set_time_signature(&mut self, time_signature: TimeSignature) -> &mut Self {
+        self.time_signature = time_signature;
+        self
+    }
+}
+
+impl Iterator for MusicTimeCounter {
+    type Item = MusicTime;
+
+    /// Advance this counter by 1 beat interval and get the new current time. The counter
+    /// never runs out of music times.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{music_time::MusicTime, music_time_counter::MusicTimeCounter};
+    /// let mut timer = MusicTimeCounter::default();
+    /// let times: Vec<MusicTime> = timer.by_ref().take(8).collect();
+    /// assert_eq!(times.last(), Some(&MusicTime::new(1, 2, 1)));
+    /// assert_eq!(timer.current_time(), &MusicTime::new(1, 2, 1));
+    /// ```
+    fn next(&mut self) -> Option<MusicTime> {
+        self.advance_beat_interval();
+        Some(self.current_time)
+    }
 }
 
 impl Default for MusicTimeCounter {
@@ -114,6 +366,102 @@ mod tests {
         assert_eq!(duration, expected);
     }
 
+    #[test]
+    fn test_tuplet_resolution() {
+        use crate::{
+            music_time::MusicTime, music_time_counter::MusicTimeCounter,
+            time_signature::TimeSignature,
+        };
+
+        for &resolution in &[3, 6, 12] {
+            let mut timer =
+                MusicTimeCounter::new(TimeSignature::new(4, 4)).with_resolution(resolution);
+            let beat = timer.beat_target_frames(90.0);
+            let sum = timer.beat_interval_target_frames(90.0) * u32::from(resolution);
+            assert!(beat - sum <= std::time::Duration::from_nanos(u64::from(resolution)));
+
+            for _ in 0..resolution {
+                timer.advance_beat_interval();
+            }
+            assert_eq!(timer.current_time(), &MusicTime::new(1, 2, 1));
+        }
+    }
+
+    #[test]
+    fn test_intervals_per_beat() {
+        use crate::{
+            music_time::MusicTime,
+            music_time_counter::MusicTimeCounter,
+            time_signature::{TimeSignature, DEFAULT_INTERVAL_RESOLUTION},
+        };
+
+        let time_signature = TimeSignature::new(4, 4);
+        let timer = MusicTimeCounter::new(time_signature);
+        assert_eq!(timer.resolution(), 8);
+        assert_eq!(timer.resolution(), DEFAULT_INTERVAL_RESOLUTION);
+
+        // A buffer sized from a custom resolution holds a beat of beat intervals
+        let mut timer = MusicTimeCounter::new(time_signature).with_resolution(12);
+        assert_eq!(timer.resolution(), 12);
+        let mut beat_intervals = vec![None; usize::from(timer.resolution())];
+        while timer.current_time().get_beat() == 1 {
+            let beat_interval = timer.current_time().get_beat_interval();
+            beat_intervals[usize::from(beat_interval - 1)] = Some(beat_interval);
+            timer.advance_beat_interval();
+        }
+        assert!(beat_intervals.iter().all(Option::is_some));
+        assert_eq!(timer.current_time(), &MusicTime::new(1, 2, 1));
+    }
+
+    #[test]
+    fn test_resolution_by_denominator() {
+        use crate::{
+            music_time::MusicTime,
+            music_time_counter::{interval_duration, MusicTimeCounter},
+            time_signature::TimeSignature,
+        };
+
+        let common = MusicTimeCounter::new(TimeSignature::new(4, 4));
+        let mut odd = MusicTimeCounter::new(TimeSignature::new(7, 16));
+        assert_eq!(common.resolution(), 8);
+        assert_eq!(odd.resolution(), common.resolution());
+
+        // The positions of a 7/16 counter are music times of 7/16
+        let time_signature = *odd.time_signature();
+        let advances = 7 * 8 + 2 * 8 + 3;
+        for _ in 0..advances {
+            odd.advance_beat_interval();
+        }
+        let position = *odd.current_time();
+        assert_eq!(position, MusicTime::new(2, 3, 4));
+        assert_eq!(MusicTime::checked_new(2, 3, 4), Some(position));
+        assert_eq!(position.to_beat_interval_index(&time_signature), advances);
+        assert_eq!(
+            MusicTime::from_beat_interval_index(advances, &time_signature),
+            position
+        );
+        assert_eq!(
+            crate::duration_between(&MusicTime::new(1, 1, 1), &position, &time_signature, 120.0),
+            interval_duration(120.0, odd.resolution()) * advances as u32
+        );
+    }
+
+    #[test]
+    fn test_midi_clock_target_frames() {
+        use crate::{music_time_counter::MusicTimeCounter, time_signature::TimeSignature};
+        use std::time::Duration;
+
+        let timer = MusicTimeCounter::new(TimeSignature::new(4, 4));
+        let duration = timer.midi_clock_target_frames(60.0);
+        let expected = Duration::from_nanos(41666666);
+        assert_eq!(duration, expected);
+
+        let timer = MusicTimeCounter::new(TimeSignature::new(6, 8));
+        let duration = timer.midi_clock_target_frames(60.0);
+        let expected = Duration::from_nanos(83333333);
+        assert_eq!(duration, expected);
+    }
+
     #[test]
     fn test_set_current_time() {
         use crate::{music_time::MusicTime, music_time_counter::MusicTimeCounter};
@@ -125,4 +473,245 @@ mod tests {
         timer.set_current_time(MusicTime::new(3, 2, 1));
         assert_eq!(timer.current_time(), &MusicTime::new(3, 2, 1));
     }
+
+    #[test]
+    fn test_set_time_signature() {
+        use crate::{
+            music_time::MusicTime, music_time_counter::MusicTimeCounter,
+            time_signature::TimeSignature,
+        };
+
+        let mut timer = MusicTimeCounter::new(TimeSignature::new(4, 4));
+        timer.advance_beat();
+        timer.advance_beat();
+        assert_eq!(timer.current_time(), &MusicTime::new(1, 3, 1));
+
+        timer.set_time_signature(TimeSignature::new(3, 4));
+        assert_eq!(timer.time_signature(), &TimeSignature::new(3, 4));
+        assert_eq!(timer.current_time(), &MusicTime::new(1, 3, 1));
+
+        timer.advance_beat();
+        assert_eq!(timer.current_time(), &MusicTime::new(2, 1, 1));
+        timer.advance_beat();
+        timer.advance_beat();
+        timer.advance_beat();
+        assert_eq!(timer.current_time(), &MusicTime::new(3, 1, 1));
+    }
+
+    #[test]
+    fn test_clone() {
+        use crate::{music_time::MusicTime, music_time_counter::MusicTimeCounter};
+
+        let mut a = MusicTimeCounter::default();
+        a.advance_beat();
+        let mut b = a.clone();
+        assert_eq!(a.current_time(), b.current_time());
+
+        a.advance_beat();
+        b.advance_beat_interval();
+        assert_eq!(a.current_time(), &MusicTime::new(1, 3, 1));
+        assert_eq!(b.current_time(), &MusicTime::new(1, 2, 2));
+    }
+
+    #[test]
+    fn test_iterator() {
+        use crate::{
+            music_time::MusicTime, music_time_counter::MusicTimeCounter,
+            time_signature::TimeSignature,
+        };
+
+        let mut timer = MusicTimeCounter::new(TimeSignature::new(4, 4));
+        let times: Vec<MusicTime> = timer.by_ref().take(32).collect();
+
+        let mut expected = Vec::new();
+        for beat in 1..=4 {
+            for beat_interval in 1..=8 {
+                expected.push(MusicTime::new(1, beat, beat_interval));
+            }
+        }
+        expected.remove(0);
+        expected.push(MusicTime::new(2, 1, 1));
+        assert_eq!(times, expected);
+        assert_eq!(timer.current_time(), &MusicTime::new(2, 1, 1));
+
+        assert_eq!(timer.next(), Some(MusicTime::new(2, 1, 2)));
+    }
+
+    #[test]
+    fn test_cross_rhythm() {
+        use crate::{
+            music_time::MusicTime, music_time_counter::MusicTimeCounter,
+            time_signature::TimeSignature,
+        };
+
+        let mut timer = MusicTimeCounter::new(TimeSignature::new(4, 4));
+        let mut cross = timer.cross_rhythm(3, 4);
+        assert_eq!(cross.resolution(), 6);
+        assert_eq!(cross.current_time(), &MusicTime::new(1, 1, 1));
+
+        // 3 cross beat intervals span 4 beat intervals in real time
+        let bpm = 100.0;
+        let span = timer.beat_interval_target_frames(bpm) * 4;
+        let cross_span = cross.beat_interval_target_frames(bpm) * 3;
+        assert!(span.abs_diff(cross_span).as_nanos() < 10);
+
+        // Both reach half a beat together
+        for _ in 0..4 {
+            timer.advance_beat_interval();
+        }
+        for _ in 0..3 {
+            cross.advance_beat_interval();
+        }
+        assert_eq!(timer.current_time(), &MusicTime::new(1, 1, 5));
+        assert_eq!(cross.current_time(), &MusicTime::new(1, 1, 4));
+
+        // Both reach the next beat together
+        for _ in 0..4 {
+            timer.advance_beat_interval();
+        }
+        for _ in 0..3 {
+            cross.advance_beat_interval();
+        }
+        assert_eq!(timer.current_time(), &MusicTime::new(1, 2, 1));
+        assert_eq!(cross.current_time(), &MusicTime::new(1, 2, 1));
+
+        // Starts from the current time
+        timer.set_current_time(MusicTime::new(3, 2, 5));
+        assert_eq!(
+            timer.cross_rhythm(3, 4).current_time(),
+            &MusicTime::new(3, 2, 4)
+        );
+    }
+
+    #[test]
+    fn test_remaining_intervals_in_bar() {
+        use crate::{
+            music_time::MusicTime, music_time_counter::MusicTimeCounter,
+            time_signature::TimeSignature,
+        };
+
+        let mut timer = MusicTimeCounter::new(TimeSignature::new(4, 4));
+        assert_eq!(timer.remaining_intervals_in_bar(), 32);
+
+        timer.set_current_time(MusicTime::new(2, 3, 5));
+        assert_eq!(timer.remaining_intervals_in_bar(), 12);
+        for _ in 0..12 {
+            timer.advance_beat_interval();
+        }
+        assert_eq!(timer.current_time(), &MusicTime::new(3, 1, 1));
+
+        timer.set_current_time(MusicTime::new(2, 4, 8));
+        assert_eq!(timer.remaining_intervals_in_bar(), 1);
+
+        let mut timer = MusicTimeCounter::new(TimeSignature::new(3, 4)).with_resolution(3);
+        timer.set_current_time(MusicTime::new(1, 2, 2));
+        assert_eq!(timer.remaining_intervals_in_bar(), 5);
+    }
+
+    #[test]
+    fn test_durations() {
+        use crate::{
+            music_time_counter::{beat_duration, interval_duration, MusicTimeCounter},
+            time_signature::TimeSignature,
+        };
+        use std::time::Duration;
+
+        let timer = MusicTimeCounter::new(TimeSignature::new(4, 4));
+        for bpm in [60.0, 120.0].iter() {
+            assert_eq!(beat_duration(*bpm), timer.beat_target_frames(*bpm));
+            assert_eq!(
+                interval_duration(*bpm, timer.resolution()),
+                timer.beat_interval_target_frames(*bpm)
+            );
+        }
+        assert_eq!(beat_duration(60.0), Duration::from_secs(1));
+        assert_eq!(interval_duration(60.0, 8), Duration::from_millis(125));
+
+        let timer = MusicTimeCounter::new(TimeSignature::new(6, 8)).with_resolution(3);
+        assert_eq!(
+            interval_duration(120.0, 3),
+            timer.beat_interval_target_frames(120.0)
+        );
+    }
+
+    #[test]
+    fn test_resolution_agrees() {
+        use crate::{
+            music_time::MusicTime,
+            music_time_counter::MusicTimeCounter,
+            time_signature::{TimeSignature, DEFAULT_INTERVAL_RESOLUTION},
+        };
+
+        for denominator in [2, 3, 4, 8, 16, 32].iter() {
+            let time_signature = TimeSignature::new(4, *denominator);
+            let mut timer = MusicTimeCounter::new(time_signature);
+            assert_eq!(timer.resolution(), DEFAULT_INTERVAL_RESOLUTION);
+
+            // The advance logic of MusicTime and the counter agree for a bar and a beat
+            let mut time = MusicTime::default();
+            for _ in 0..(4 + 1) * DEFAULT_INTERVAL_RESOLUTION {
+                time.advance_beat_interval(&time_signature);
+                timer.advance_beat_interval();
+                assert_eq!(timer.current_time(), &time);
+            }
+            assert_eq!(time, MusicTime::new(2, 2, 1));
+
+            // The beat intervals of a beat last a beat
+            let bpm = 120.0;
+            let beat_duration =
+                timer.beat_interval_target_frames(bpm) * u32::from(DEFAULT_INTERVAL_RESOLUTION);
+            let beat_target = timer.beat_target_frames(bpm);
+            assert!(beat_duration.abs_diff(beat_target).as_nanos() < 32);
+            assert_eq!(
+                crate::duration_between(
+                    &MusicTime::new(1, 1, 1),
+                    &MusicTime::new(1, 2, 1),
+                    &time_signature,
+                    bpm
+                ),
+                beat_target
+            );
+        }
+    }
+
+    #[test]
+    fn test_free_running() {
+        use crate::{
+            music_time::MusicTime, music_time_counter::MusicTimeCounter,
+            time_signature::TimeSignature,
+        };
+
+        let mut timer = MusicTimeCounter::new(TimeSignature::new(3, 4));
+        timer.set_free_running(true);
+        assert!(timer.is_free_running());
+        for _ in 0..8 * 10 {
+            timer.advance_beat_interval();
+        }
+        assert_eq!(timer.current_time(), &MusicTime::new(1, 11, 1));
+
+        timer.set_current_time(MusicTime::new(1, u8::MAX, 8));
+        timer.advance_beat_interval();
+        assert_eq!(timer.current_time(), &MusicTime::new(1, 1, 1));
+
+        timer.set_free_running(false);
+        timer.set_current_time(MusicTime::new(1, 3, 8));
+        timer.advance_beat_interval();
+        assert_eq!(timer.current_time(), &MusicTime::new(2, 1, 1));
+    }
+
+    #[test]
+    fn test_new_at() {
+        use crate::{
+            music_time::MusicTime, music_time_counter::MusicTimeCounter,
+            time_signature::TimeSignature,
+        };
+
+        let mut timer = MusicTimeCounter::new_at(TimeSignature::new(4, 4), MusicTime::new(5, 2, 3));
+        assert_eq!(timer.current_time(), &MusicTime::new(5, 2, 3));
+        assert_eq!(timer.time_signature(), &TimeSignature::new(4, 4));
+        assert_eq!(timer.resolution(), 8);
+
+        timer.advance_beat();
+        assert_eq!(timer.current_time(), &MusicTime::new(5, 3, 3));
+    }
 }
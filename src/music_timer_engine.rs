@@ -5,12 +5,214 @@
 //!
 
 use super::{
-  music_time::MusicTime, music_time_counter::MusicTimeCounter, time_signature::TimeSignature,
+  clock::{Clock, ManualClock, SystemClock},
+  music_time::MusicTime,
+  music_time_counter::MusicTimeCounter,
+  scheduler::Scheduler,
+  tempo_map::TempoMap,
+  time_signature::{TimeSignature, TimeSignatureError},
+};
+use std::{
+  convert::TryFrom,
+  error::Error,
+  fmt,
+  ops::ControlFlow,
+  sync::mpsc::Sender,
+  time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use std::time::{Duration, SystemTime};
 
 const STRING_PANIC_TIME_FLOW: &str = "Hello John Titor, you reversed time!";
 
+/// A MIDI clock pulse is 1/24 of a quarter note and a beat interval is 1/8 of a beat, so
+/// each pulse advances the denominator of the time signature in twelfths of a beat interval.
+const EXTERNAL_CLOCK_PHASE_PER_BEAT_INTERVAL: u16 = 12;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Errors that can occur when the engine reads the flow of time.
+pub enum TimeError {
+  /// The clock reported a time earlier than the previous pulse, _e.g._ the system
+  /// clock was adjusted backwards.
+  TimeReversed,
+}
+
+impl fmt::Display for TimeError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      TimeError::TimeReversed => write!(f, "{}", STRING_PANIC_TIME_FLOW),
+    }
+  }
+}
+
+impl Error for TimeError {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Errors that can occur when setting a loop region.
+pub enum LoopError {
+  /// The end of the loop is before its start.
+  TooShort,
+}
+
+impl fmt::Display for LoopError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      LoopError::TooShort => write!(f, "The loop must be at least one beat interval long"),
+    }
+  }
+}
+
+impl Error for LoopError {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Errors that can occur when setting the playback speed.
+pub enum SpeedError {
+  /// The speed multiplier must be finite and greater than zero.
+  NotPositive,
+}
+
+impl fmt::Display for SpeedError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SpeedError::NotPositive => write!(f, "The speed multiplier must be greater than zero"),
+    }
+  }
+}
+
+impl Error for SpeedError {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Errors that can occur when setting the bpm.
+pub enum BpmError {
+  /// The bpm must be finite and greater than zero.
+  NotPositive,
+}
+
+impl fmt::Display for BpmError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      BpmError::NotPositive => write!(f, "The bpm must be finite and greater than zero"),
+    }
+  }
+}
+
+impl Error for BpmError {}
+
+/// Check a bpm is finite and greater than zero. A bpm of zero would wait forever for
+/// the next beat interval.
+pub(crate) fn validate_bpm(bpm: f32) -> Result<(), BpmError> {
+  if bpm > 0.0 && bpm.is_finite() {
+    Ok(())
+  } else {
+    Err(BpmError::NotPositive)
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Errors that can occur when setting the swing.
+pub enum SwingError {
+  /// The swing ratio must be between `0.5` and `0.75`.
+  OutOfRange,
+}
+
+impl fmt::Display for SwingError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SwingError::OutOfRange => write!(f, "The swing ratio must be between 0.5 and 0.75"),
+    }
+  }
+}
+
+impl Error for SwingError {}
+
+/// Check a swing ratio is within the range `set_swing` clamps to.
+fn validate_swing(ratio: f32) -> Result<(), SwingError> {
+  if (0.5..=0.75).contains(&ratio) {
+    Ok(())
+  } else {
+    Err(SwingError::OutOfRange)
+  }
+}
+
+/// Panics with a message describing why the bpm is invalid.
+fn assert_bpm(bpm: f32) {
+  if let Err(error) = validate_bpm(bpm) {
+    panic!("{}, got {}", error, bpm);
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Errors that can occur when building a `MusicTimerEngine`.
+pub enum BuildError {
+  /// The time signature is invalid.
+  TimeSignature(TimeSignatureError),
+  /// The loop region is invalid.
+  Loop(LoopError),
+  /// The bpm is invalid.
+  Bpm(BpmError),
+  /// The swing ratio is invalid.
+  Swing(SwingError),
+}
+
+impl fmt::Display for BuildError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      BuildError::TimeSignature(error) => write!(f, "{}", error),
+      BuildError::Loop(error) => write!(f, "{}", error),
+      BuildError::Bpm(error) => write!(f, "{}", error),
+      BuildError::Swing(error) => write!(f, "{}", error),
+    }
+  }
+}
+
+impl Error for BuildError {}
+
+impl From<TimeSignatureError> for BuildError {
+  fn from(error: TimeSignatureError) -> Self {
+    BuildError::TimeSignature(error)
+  }
+}
+
+impl From<LoopError> for BuildError {
+  fn from(error: LoopError) -> Self {
+    BuildError::Loop(error)
+  }
+}
+
+impl From<BpmError> for BuildError {
+  fn from(error: BpmError) -> Self {
+    BuildError::Bpm(error)
+  }
+}
+
+impl From<SwingError> for BuildError {
+  fn from(error: SwingError) -> Self {
+    BuildError::Swing(error)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The changes in music time that happened during a pulse of `MusicTimerEngine`.
+pub enum PulseEvent {
+  /// Music time did not change.
+  Nothing,
+  /// The beat interval changed.
+  BeatInterval,
+  /// The beat changed.
+  Beat,
+  /// The bar changed.
+  Bar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The transport state of a `MusicTimerEngine`.
+pub enum Transport {
+  /// The performance is stopped at its start, see `MusicTimerEngine::stop`.
+  Stopped,
+  /// The performance is playing and pulses advance music time.
+  Playing,
+  /// The performance is paused and resumes from where it was, see `MusicTimerEngine::pause`.
+  Paused,
+}
+
 /// This trait is used by `MusicTimerEngine` for callbacks in changes of music time.
 /// Invoke it to make the most of the performance engine.
 pub trait MusicTimerState {
@@ -20,30 +222,345 @@ pub trait MusicTimerState {
   /// - `current_time` - The current time at which this callback has been triggered.
   fn on_beat_interval(&mut self, current_time: &MusicTime);
 
+  /// Called when the beat interval changes, with the number of beat intervals performed
+  /// before it. Handy for addressing a flat array, _e.g._ automation. Calls
+  /// `on_beat_interval` by default.
+  ///
+  /// # Arguments
+  /// - `current_time` - The current time at which this callback has been triggered.
+  /// - `index` - The flat index of the beat interval, `0` for the first of the performance.
+  fn on_beat_interval_indexed(&mut self, current_time: &MusicTime, _index: u64) {
+    self.on_beat_interval(current_time);
+  }
+
   /// Called when the beat changes.
   ///
   /// # Arguments
   /// - `current_time` - The current time at which this callback has been triggered.
   fn on_beat(&mut self, current_time: &MusicTime);
 
-  /// Called when the bar changes
+  /// Called when the bar changes. This is not called for the bar the performance starts on.
   ///
   /// # Arguments
   /// - `current_time` - The current time at which this callback has been triggered.
   fn on_bar(&mut self, current_time: &MusicTime);
+
+  /// Called after `on_bar` when a phrase starts, every `set_phrase_length` bars from bar 1.
+  /// This is not called for the phrase the performance starts on. Does nothing by default.
+  ///
+  /// # Arguments
+  /// - `current_time` - The current time at which this callback has been triggered.
+  fn on_phrase(&mut self, _current_time: &MusicTime) {}
+
+  /// Called on every beat after `on_beat`, with the downbeat of the bar accented. In a
+  /// compound meter, see `TimeSignature::is_compound`, the first beat of each group of
+  /// three is accented too. Does nothing by default.
+  ///
+  /// # Arguments
+  /// - `current_time` - The current time at which this callback has been triggered.
+  /// - `accent` - `true` if the beat is the first beat of the bar, or of a group of three
+  ///   beats in a compound meter.
+  fn on_metronome(&mut self, _current_time: &MusicTime, _accent: bool) {}
+
+  /// Called when the performance loops back to the start of the loop region.
+  /// Does nothing by default.
+  ///
+  /// # Arguments
+  /// - `current_time` - The start of the loop region the performance has jumped to.
+  fn on_loop(&mut self, _current_time: &MusicTime) {}
+
+  /// Called on every MIDI clock pulse, 24 per quarter note, while the MIDI clock is started.
+  /// Does nothing by default.
+  fn on_midi_clock(&mut self) {}
+
+  /// Called when the MIDI clock is started. Does nothing by default.
+  fn on_midi_start(&mut self) {}
+
+  /// Called when the MIDI clock is stopped. Does nothing by default.
+  fn on_midi_stop(&mut self) {}
+
+  /// Called on the first pulse of the performance, before any other callback.
+  /// Does nothing by default.
+  fn on_start(&mut self) {}
+
+  /// Called when the performance is stopped, by `stop_with` or on the next pulse after
+  /// `stop`. Does nothing by default.
+  fn on_stop(&mut self) {}
+
+  /// Called after a pulse that had to trigger more than one beat interval because the
+  /// engine was pulsed too late, _e.g._ the frame rate of the loop is too low.
+  /// Does nothing by default.
+  ///
+  /// # Arguments
+  /// - `missed` - The number of beat intervals that were late and caught up.
+  fn on_underrun(&mut self, _missed: u32) {}
+
+  /// Checked by the engine after each change in music time callback. Return
+  /// `ControlFlow::Break` to stop the performance as with `MusicTimerEngine::stop`,
+  /// _e.g._ from `on_bar` once the end is reached. No further changes in music time are
+  /// triggered in that pulse. Continues by default.
+  fn control_flow(&self) -> ControlFlow<()> {
+    ControlFlow::Continue(())
+  }
+}
+
+/// Broadcasts the callbacks of `MusicTimerState` to many listeners in order.
+struct MusicTimerListeners<'a, 'b>(&'a mut [&'b mut dyn MusicTimerState]);
+
+impl MusicTimerState for MusicTimerListeners<'_, '_> {
+  fn on_beat_interval(&mut self, current_time: &MusicTime) {
+    for state in self.0.iter_mut() {
+      state.on_beat_interval(current_time);
+    }
+  }
+
+  fn on_beat_interval_indexed(&mut self, current_time: &MusicTime, index: u64) {
+    for state in self.0.iter_mut() {
+      state.on_beat_interval_indexed(current_time, index);
+    }
+  }
+
+  fn on_beat(&mut self, current_time: &MusicTime) {
+    for state in self.0.iter_mut() {
+      state.on_beat(current_time);
+    }
+  }
+
+  fn on_bar(&mut self, current_time: &MusicTime) {
+    for state in self.0.iter_mut() {
+      state.on_bar(current_time);
+    }
+  }
+
+  fn on_phrase(&mut self, current_time: &MusicTime) {
+    for state in self.0.iter_mut() {
+      state.on_phrase(current_time);
+    }
+  }
+
+  fn on_metronome(&mut self, current_time: &MusicTime, accent: bool) {
+    for state in self.0.iter_mut() {
+      state.on_metronome(current_time, accent);
+    }
+  }
+
+  fn on_loop(&mut self, current_time: &MusicTime) {
+    for state in self.0.iter_mut() {
+      state.on_loop(current_time);
+    }
+  }
+
+  fn on_midi_clock(&mut self) {
+    for state in self.0.iter_mut() {
+      state.on_midi_clock();
+    }
+  }
+
+  fn on_midi_start(&mut self) {
+    for state in self.0.iter_mut() {
+      state.on_midi_start();
+    }
+  }
+
+  fn on_midi_stop(&mut self) {
+    for state in self.0.iter_mut() {
+      state.on_midi_stop();
+    }
+  }
+
+  fn on_start(&mut self) {
+    for state in self.0.iter_mut() {
+      state.on_start();
+    }
+  }
+
+  fn on_stop(&mut self) {
+    for state in self.0.iter_mut() {
+      state.on_stop();
+    }
+  }
+
+  fn on_underrun(&mut self, missed: u32) {
+    for state in self.0.iter_mut() {
+      state.on_underrun(missed);
+    }
+  }
+
+  fn control_flow(&self) -> ControlFlow<()> {
+    for state in self.0.iter() {
+      state.control_flow()?;
+    }
+    ControlFlow::Continue(())
+  }
+}
+
+type MusicTimeCallback = Box<dyn FnMut(&MusicTime) + Send>;
+type OneShotCallback = Box<dyn FnOnce() + Send>;
+
+/// Closures set on the engine for changes in music time.
+#[derive(Default)]
+struct MusicTimerClosures {
+  on_beat_interval: Option<MusicTimeCallback>,
+  on_beat: Option<MusicTimeCallback>,
+  on_bar: Option<MusicTimeCallback>,
+  one_shots: Scheduler<OneShotCallback>,
+}
+
+impl fmt::Debug for MusicTimerClosures {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("MusicTimerClosures")
+      .field("on_beat_interval", &self.on_beat_interval.is_some())
+      .field("on_beat", &self.on_beat.is_some())
+      .field("on_bar", &self.on_bar.is_some())
+      .field("one_shots", &self.one_shots.len())
+      .finish()
+  }
+}
+
+impl MusicTimerState for MusicTimerClosures {
+  fn on_beat_interval(&mut self, current_time: &MusicTime) {
+    if let Some(callback) = &mut self.on_beat_interval {
+      callback(current_time);
+    }
+    for callback in self.one_shots.advance(current_time) {
+      callback();
+    }
+  }
+
+  fn on_beat(&mut self, current_time: &MusicTime) {
+    if let Some(callback) = &mut self.on_beat {
+      callback(current_time);
+    }
+  }
+
+  fn on_bar(&mut self, current_time: &MusicTime) {
+    if let Some(callback) = &mut self.on_bar {
+      callback(current_time);
+    }
+  }
+}
+
+/// Sends changes in music time into a channel.
+struct MusicTimerSender<'a>(&'a Sender<(PulseEvent, MusicTime)>);
+
+impl<'a> MusicTimerState for MusicTimerSender<'a> {
+  // A disconnected receiver has no interest in the events, so send errors are ignored
+  fn on_beat_interval(&mut self, current_time: &MusicTime) {
+    let _ = self.0.send((PulseEvent::BeatInterval, *current_time));
+  }
+
+  fn on_beat(&mut self, current_time: &MusicTime) {
+    let _ = self.0.send((PulseEvent::Beat, *current_time));
+  }
+
+  fn on_bar(&mut self, current_time: &MusicTime) {
+    let _ = self.0.send((PulseEvent::Bar, *current_time));
+  }
+}
+
+/// A linear change of bpm between two music times.
+#[derive(Debug)]
+struct TempoRamp {
+  start_bpm: f32,
+  target_bpm: f32,
+  start_time: MusicTime,
+  end_time: MusicTime,
+}
+
+/// Pseudo-random deviation of beat interval trigger times around the grid.
+#[derive(Debug)]
+struct Humanize {
+  max_jitter: Duration,
+  rng_state: u64,
+  jitter: f32,
+}
+
+impl Humanize {
+  fn new(max_jitter: Duration, seed: u64) -> Self {
+    Humanize {
+      max_jitter,
+      // Xorshift is stuck at zero
+      rng_state: if seed == 0 {
+        0x9E37_79B9_7F4A_7C15
+      } else {
+        seed
+      },
+      jitter: 0.0,
+    }
+  }
+
+  /// Draw the jitter of the next beat interval with xorshift64, from `-1.0` to `1.0`
+  /// of the max jitter.
+  fn advance(&mut self) {
+    self.rng_state ^= self.rng_state << 13;
+    self.rng_state ^= self.rng_state >> 7;
+    self.rng_state ^= self.rng_state << 17;
+    let unit = (self.rng_state >> 40) as f32 / (1u64 << 24) as f32;
+    self.jitter = unit * 2.0 - 1.0;
+  }
+
+  /// Gets how early and how late the next beat interval is triggered.
+  fn offsets(&self) -> (Duration, Duration) {
+    let offset = self.max_jitter.mul_f32(self.jitter.abs());
+    if self.jitter < 0.0 {
+      (offset, Duration::default())
+    } else {
+      (Duration::default(), offset)
+    }
+  }
+}
+
+/// Count the beat intervals from `start` up to `end` within a time signature.
+fn beat_intervals_between(
+  start: &MusicTime,
+  end: &MusicTime,
+  time_signature: &TimeSignature,
+) -> i64 {
+  end.to_beat_interval_index(time_signature) - start.to_beat_interval_index(time_signature)
 }
 
 /// The engine uses all of this crate's utilities to allow to use of a music
 /// performance state system that triggers callbacks. Its aims are to allow
-/// for an easy interface for changes in music time.
-pub struct MusicTimerEngine {
+/// for an easy interface for changes in music time. Time is read from a `Clock`,
+/// by default the system time.
+#[derive(Debug)]
+pub struct MusicTimerEngine<C: Clock = SystemClock> {
+  clock: C,
   total_time: Duration,
   previous_time: Duration,
-  start_time: SystemTime,
+  start_time: Duration,
   event_trigger_time: Duration,
   music_counter: MusicTimeCounter,
   event_trigger_target: Duration,
   previous_music_time: MusicTime,
+  total_beat_intervals: u64,
+  bpm: f32,
+  tempo_ramp: Option<TempoRamp>,
+  tempo_map: Option<TempoMap>,
+  swing: f32,
+  swing_grid: u8,
+  groove: Vec<Duration>,
+  phrase_length: u16,
+  count_in_bars: u16,
+  count_in_bars_remaining: u16,
+  loop_region: Option<(MusicTime, MusicTime)>,
+  speed: f32,
+  closures: MusicTimerClosures,
+  midi_clock_time: Option<Duration>,
+  external_clock_phase: u16,
+  transport: Transport,
+  is_started: bool,
+  is_stop_pending: bool,
+  is_waiting_for_bar: bool,
+  is_start_scheduled: bool,
+  is_held: bool,
+  output_latency: Duration,
+  output_delay: Duration,
+  event_trigger_debt: Duration,
+  humanize: Option<Humanize>,
+  next_time_signature: Option<TimeSignature>,
+  performance_time: Duration,
+  recording: Option<Vec<(Duration, PulseEvent, MusicTime)>>,
 }
 
 impl MusicTimerEngine {
@@ -53,27 +570,212 @@ impl MusicTimerEngine {
   /// * `time_signature` - The time signature for the performance.
   /// * `bpm` - The beats per minute used for the performance.
   ///
+  /// # Panics
+  /// Panics if `bpm` is not finite and greater than zero.
+  ///
   /// # Example
   /// ```
   /// use music_timer::{music_timer_engine::MusicTimerEngine, time_signature::TimeSignature};
   /// let mut performer = MusicTimerEngine::new(TimeSignature::new(3, 4), 155.0);
   /// ```
   pub fn new(time_signature: TimeSignature, bpm: f32) -> Self {
+    MusicTimerEngine::with_clock(time_signature, bpm, SystemClock)
+  }
+
+  /// Start the performance at a `SystemTime` instead of from when the engine was created,
+  /// _e.g._ a time agreed upon by machines on a network to start together. Pulses do
+  /// nothing until `start` has passed. A `start` in the past catches the performance up.
+  ///
+  /// # Arguments
+  /// * `start` - The time the performance starts at.
+  ///
+  /// # Example
+  /// ```
+  /// use std::time::{Duration, SystemTime};
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.set_start_time(SystemTime::now() + Duration::from_millis(500));
+  /// ```
+  pub fn set_start_time(&mut self, start: SystemTime) -> &mut Self {
+    self.start_time = start.duration_since(UNIX_EPOCH).unwrap_or_default();
+    self.total_time = Duration::default();
+    self.previous_time = Duration::default();
+    self.is_start_scheduled = true;
+    self
+  }
+}
+
+impl<C: Clock> MusicTimerEngine<C> {
+  /// Create a new `MusicTimerEngine` with a `TimeSignature`, bpm and the `Clock`
+  /// time is read from.
+  ///
+  /// # Arguments
+  /// * `time_signature` - The time signature for the performance.
+  /// * `bpm` - The beats per minute used for the performance.
+  /// * `clock` - The source of time for the performance.
+  ///
+  /// # Panics
+  /// Panics if `bpm` is not finite and greater than zero.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::{
+  ///     clock::ManualClock, music_timer_engine::MusicTimerEngine, time_signature::TimeSignature,
+  /// };
+  /// let mut performer = MusicTimerEngine::with_clock(TimeSignature::new(3, 4), 155.0, ManualClock::new());
+  /// ```
+  pub fn with_clock(time_signature: TimeSignature, bpm: f32, clock: C) -> Self {
+    assert_bpm(bpm);
     let music_counter = MusicTimeCounter::new(time_signature);
     let event_trigger_target = music_counter.beat_interval_target_frames(bpm);
+    let start_time = clock.now();
     MusicTimerEngine {
+      clock,
       total_time: Duration::default(),
       previous_time: Duration::default(),
-      start_time: SystemTime::now(),
+      start_time,
       event_trigger_time: event_trigger_target,
       music_counter,
       event_trigger_target,
       previous_music_time: MusicTime::new(0, 0, 0),
+      total_beat_intervals: 0,
+      bpm,
+      tempo_ramp: None,
+      tempo_map: None,
+      swing: 0.5,
+      swing_grid: 1,
+      groove: Vec::new(),
+      phrase_length: 0,
+      count_in_bars: 0,
+      count_in_bars_remaining: 0,
+      loop_region: None,
+      speed: 1.0,
+      closures: MusicTimerClosures::default(),
+      midi_clock_time: None,
+      external_clock_phase: EXTERNAL_CLOCK_PHASE_PER_BEAT_INTERVAL,
+      transport: Transport::Playing,
+      is_started: false,
+      is_stop_pending: false,
+      is_waiting_for_bar: false,
+      is_start_scheduled: false,
+      is_held: false,
+      output_latency: Duration::default(),
+      output_delay: Duration::default(),
+      event_trigger_debt: Duration::default(),
+      humanize: None,
+      next_time_signature: None,
+      performance_time: Duration::default(),
+      recording: None,
+    }
+  }
+
+  /// Set the closure called when the beat interval changes, triggered by `pulse_closures`.
+  /// Closures must be `Send` so the engine can be moved to another thread, see
+  /// `performance_thread::spawn`.
+  ///
+  /// # Arguments
+  /// * `callback` - The closure called with the current time.
+  ///
+  /// # Example
+  /// ```
+  /// let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap()
+  ///     .on_beat_interval(|current_time| println!("{:?}", current_time));
+  /// performer.pulse_closures();
+  /// ```
+  pub fn on_beat_interval<F: FnMut(&MusicTime) + Send + 'static>(mut self, callback: F) -> Self {
+    self.closures.on_beat_interval = Some(Box::new(callback));
+    self
+  }
+
+  /// Set the closure called when the beat changes, triggered by `pulse_closures`.
+  ///
+  /// # Arguments
+  /// * `callback` - The closure called with the current time.
+  pub fn on_beat<F: FnMut(&MusicTime) + Send + 'static>(mut self, callback: F) -> Self {
+    self.closures.on_beat = Some(Box::new(callback));
+    self
+  }
+
+  /// Set the closure called when the bar changes, triggered by `pulse_closures`.
+  ///
+  /// # Arguments
+  /// * `callback` - The closure called with the current time.
+  pub fn on_bar<F: FnMut(&MusicTime) + Send + 'static>(mut self, callback: F) -> Self {
+    self.closures.on_bar = Some(Box::new(callback));
+    self
+  }
+
+  /// Register a closure called once when the performance reaches or passes a music time,
+  /// triggered by `pulse_closures`. It is removed once called, so it is not called again
+  /// when the performance loops or seeks back over the music time. Closures registered
+  /// for the same music time are called in the order they were registered.
+  ///
+  /// # Arguments
+  /// * `time` - The music time to call the closure at.
+  /// * `callback` - The closure to call.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::music_time::MusicTime;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.at(MusicTime::new(5, 1, 1), || println!("Chorus!"));
+  /// performer.pulse_closures();
+  /// ```
+  pub fn at<F: FnOnce() + Send + 'static>(&mut self, time: MusicTime, callback: F) -> &mut Self {
+    self.closures.one_shots.schedule(time, Box::new(callback));
+    self
+  }
+
+  /// Count in the performance. The callbacks are triggered for the count in bars before
+  /// the performance starts at `MusicTime::new(1, 1, 1)`. Each count in bar is reported
+  /// as bar `0`.
+  ///
+  /// # Arguments
+  /// * `bars` - The number of bars to count in.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::music_time::MusicTime;
+  /// let performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap().with_count_in(1);
+  /// assert_eq!(performer.get_current_time(), &MusicTime::new(0, 1, 1));
+  /// ```
+  pub fn with_count_in(mut self, bars: u16) -> Self {
+    self.count_in_bars = bars;
+    self.count_in_bars_remaining = bars;
+    if bars > 0 {
+      self.music_counter.set_current_time(MusicTime::new(0, 1, 1));
     }
+    self
+  }
+
+  /// Delay the start of the performance by a fixed real time from now, _e.g._ the leader
+  /// of a video. Pulses do nothing until the delay has elapsed on the clock, then the
+  /// performance starts at `MusicTime::new(1, 1, 1)`. Unlike `with_count_in` no callbacks
+  /// are triggered during the delay. `tick` and `advance_by` do not wait for it.
+  ///
+  /// # Arguments
+  /// * `delay` - The time to wait before starting.
+  ///
+  /// # Example
+  /// ```
+  /// use std::time::Duration;
+  /// let performer = music_timer::create_performance_engine(4, 4, 120.0)
+  ///     .unwrap()
+  ///     .with_start_delay(Duration::from_secs(2));
+  /// ```
+  pub fn with_start_delay(mut self, delay: Duration) -> Self {
+    self.start_time = self.clock.now() + delay;
+    self.total_time = Duration::default();
+    self.previous_time = Duration::default();
+    self.is_start_scheduled = true;
+    self
   }
 
   /// Pulse the engine. The time since the last pulse is used to evaluate if there is
-  /// a change in music time. It is suggested to call this from a loop.
+  /// a change in music time. It is suggested to call this from a loop. If more than one
+  /// beat interval has elapsed since the last pulse, the callbacks are triggered for each
+  /// of them in order. The changes in music time are also returned in the order they
+  /// happened, or `PulseEvent::Nothing` if there were none. Nothing happens unless the
+  /// transport is `Transport::Playing`.
   ///
   /// # Arguments
   /// * `state` - The _trait_ `MusicTimerState` used for changes in music time callbacks.TimeSignature
@@ -94,58 +796,623 @@ impl MusicTimerEngine {
   ///     }
   /// }
   /// let mut performer_state = PerformanceState{};
-  /// let mut performer = music_timer::create_performance_engine(3, 4, 155.0);
+  /// let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
   /// performer.pulse(&mut performer_state);
   /// ```
-  pub fn pulse<TimerState: MusicTimerState>(&mut self, state: &mut TimerState) {
-    // Progress total time
-    self.previous_time = self.total_time;
-    // Time should never reverse else you're in trouble
-    self.total_time = SystemTime::now()
-      .duration_since(self.start_time)
-      .expect(STRING_PANIC_TIME_FLOW);
-
-    // Advance by delta
-    let time_delta = self.total_time - self.previous_time;
-    self.event_trigger_time += time_delta;
+  ///
+  /// # Panics
+  /// Panics if time has reversed since the last pulse. Use `try_pulse` to handle this.
+  pub fn pulse<TimerState: MusicTimerState>(&mut self, state: &mut TimerState) -> Vec<PulseEvent> {
+    self.try_pulse(state).expect(STRING_PANIC_TIME_FLOW)
+  }
 
-    // Check for an advance in the beat interval
-    let is_beat_interval_advanced = self.event_trigger_time >= self.event_trigger_target;
-    if is_beat_interval_advanced {
-      let current_time = self.music_counter.current_time();
+  /// Pulse the engine, the same as `pulse` but the callbacks are triggered on many
+  /// states. Each callback is triggered on every state in the order of `states`
+  /// before the next callback is triggered, _e.g._ every state receives `on_beat_interval`
+  /// before any state receives `on_beat`.
+  ///
+  /// # Arguments
+  /// * `states` - The _traits_ `MusicTimerState` used for changes in music time callbacks.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::{music_timer_engine::MusicTimerState, music_time::MusicTime};
+  /// struct PerformanceState;
+  /// impl MusicTimerState for PerformanceState {
+  ///     fn on_beat_interval(&mut self, current_time: &MusicTime) {}
+  ///     fn on_beat(&mut self, current_time: &MusicTime) {}
+  ///     fn on_bar(&mut self, current_time: &MusicTime) {}
+  /// }
+  /// let mut audio_state = PerformanceState{};
+  /// let mut lighting_state = PerformanceState{};
+  /// let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
+  /// performer.pulse_all(&mut [&mut audio_state, &mut lighting_state]);
+  /// ```
+  ///
+  /// # Panics
+  /// Panics if time has reversed since the last pulse.
+  pub fn pulse_all(&mut self, states: &mut [&mut dyn MusicTimerState]) -> Vec<PulseEvent> {
+    self.pulse(&mut MusicTimerListeners(states))
+  }
+
+  /// Pulse the engine, the same as `pulse` but the closures set with `on_beat_interval`,
+  /// `on_beat` and `on_bar` are called instead of a `MusicTimerState`.
+  ///
+  /// # Example
+  /// ```
+  /// let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap()
+  ///     .on_beat(|current_time| println!("Beat {}", current_time.get_beat()))
+  ///     .on_bar(|current_time| println!("Bar {}", current_time.get_bar()));
+  /// performer.pulse_closures();
+  /// ```
+  ///
+  /// # Panics
+  /// Panics if time has reversed since the last pulse.
+  pub fn pulse_closures(&mut self) -> Vec<PulseEvent> {
+    let mut closures = std::mem::take(&mut self.closures);
+    let events = self.pulse(&mut closures);
+    self.closures = closures;
+    events
+  }
 
-      // On beat interval change
-      state.on_beat_interval(&current_time);
+  /// Pulse the engine, the same as `pulse` but each change in music time is sent into a
+  /// channel with its music time instead of calling a `MusicTimerState`. Events are not
+  /// sent once the receiver has disconnected.
+  ///
+  /// # Arguments
+  /// * `tx` - The sender of the channel to send changes in music time into.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::music_timer_engine::PulseEvent;
+  /// use std::sync::mpsc;
+  /// let (tx, rx) = mpsc::channel();
+  /// let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
+  /// performer.pulse_to_channel(&tx);
+  /// let (event, current_time) = rx.recv().unwrap();
+  /// assert_eq!(event, PulseEvent::BeatInterval);
+  /// ```
+  ///
+  /// # Panics
+  /// Panics if time has reversed since the last pulse.
+  pub fn pulse_to_channel(&mut self, tx: &Sender<(PulseEvent, MusicTime)>) -> Vec<PulseEvent> {
+    self.pulse(&mut MusicTimerSender(tx))
+  }
 
-      // On beat change
-      let is_beat_changed =
-        self.previous_music_time.get_beat() != self.music_counter.current_time().get_beat();
-      if is_beat_changed {
-        state.on_beat(&current_time);
-      }
+  /// Pulse the engine, the same as `pulse` but returns an error instead of panicking
+  /// if time has reversed since the last pulse. No callbacks are triggered on error,
+  /// call `rebase_start_time` to recover.
+  ///
+  /// # Arguments
+  /// * `state` - The _trait_ `MusicTimerState` used for changes in music time callbacks.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::{music_timer_engine::MusicTimerState, music_time::MusicTime};
+  /// struct PerformanceState;
+  /// impl MusicTimerState for PerformanceState {
+  ///     fn on_beat_interval(&mut self, current_time: &MusicTime) {}
+  ///     fn on_beat(&mut self, current_time: &MusicTime) {}
+  ///     fn on_bar(&mut self, current_time: &MusicTime) {}
+  /// }
+  /// let mut performer_state = PerformanceState{};
+  /// let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
+  /// if performer.try_pulse(&mut performer_state).is_err() {
+  ///     performer.rebase_start_time();
+  /// }
+  /// ```
+  pub fn try_pulse<TimerState: MusicTimerState>(
+    &mut self,
+    state: &mut TimerState,
+  ) -> Result<Vec<PulseEvent>, TimeError> {
+    self.finish_stop(state);
+    if self.transport != Transport::Playing {
+      return Ok(vec![PulseEvent::Nothing]);
+    }
 
-      // On bar change
-      let is_bar_changed =
-        self.previous_music_time.get_bar() != self.music_counter.current_time().get_bar();
-      if is_bar_changed {
-        state.on_bar(&current_time);
+    // Wait for a start time set in the future
+    let now = self.clock.now();
+    if self.is_start_scheduled {
+      if now < self.start_time {
+        return Ok(vec![PulseEvent::Nothing]);
       }
+      self.is_start_scheduled = false;
+    }
+
+    // Time should never reverse else you're in trouble
+    let total_time = now
+      .checked_sub(self.start_time)
+      .ok_or(TimeError::TimeReversed)?;
+    if total_time < self.total_time {
+      return Err(TimeError::TimeReversed);
+    }
+
+    // Progress total time
+    self.previous_time = self.total_time;
+    self.total_time = total_time;
+
+    // Advance by delta
+    let time_delta = self.total_time - self.previous_time;
+    Ok(self.advance_by(time_delta, state))
+  }
+
+  /// Advance the engine by a fixed duration instead of reading the clock. The same
+  /// callbacks as `pulse` are triggered. Handy for fixed time step update loops.
+  ///
+  /// # Arguments
+  /// * `delta` - The duration of time to advance the performance by.
+  /// * `state` - The _trait_ `MusicTimerState` used for changes in music time callbacks.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::{music_timer_engine::MusicTimerState, music_time::MusicTime};
+  /// use std::time::Duration;
+  /// struct PerformanceState;
+  /// impl MusicTimerState for PerformanceState {
+  ///     fn on_beat_interval(&mut self, current_time: &MusicTime) {}
+  ///     fn on_beat(&mut self, current_time: &MusicTime) {}
+  ///     fn on_bar(&mut self, current_time: &MusicTime) {}
+  /// }
+  /// let mut performer_state = PerformanceState{};
+  /// let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
+  /// performer.tick(Duration::from_millis(16), &mut performer_state);
+  /// ```
+  pub fn tick<TimerState: MusicTimerState>(
+    &mut self,
+    delta: Duration,
+    state: &mut TimerState,
+  ) -> Vec<PulseEvent> {
+    self.advance_by(delta, state)
+  }
+
+  fn advance_by<TimerState: MusicTimerState>(
+    &mut self,
+    delta: Duration,
+    state: &mut TimerState,
+  ) -> Vec<PulseEvent> {
+    self.finish_stop(state);
+    if self.transport != Transport::Playing {
+      return vec![PulseEvent::Nothing];
+    }
+
+    let mut events = Vec::new();
+    self.start(state);
+    self.performance_time += delta;
+    if self.is_held {
+      return vec![PulseEvent::Nothing];
+    }
+
+    // Hold back the beat intervals by the time owed to an output delay or early trigger
+    let trigger_delta = delta.saturating_sub(self.event_trigger_debt);
+    self.event_trigger_debt = self.event_trigger_debt.saturating_sub(delta);
+    self.event_trigger_time += trigger_delta;
 
-      // Advance and store time
-      self.previous_music_time = self.music_counter.current_time().clone();
-      self.music_counter.advance_beat_interval();
+    // Advance every MIDI clock pulse that has elapsed
+    if let Some(midi_clock_time) = &mut self.midi_clock_time {
+      let midi_clock_target = self
+        .music_counter
+        .midi_clock_target_frames(self.bpm * self.speed);
+      *midi_clock_time += delta;
+      while *midi_clock_time >= midi_clock_target {
+        state.on_midi_clock();
+        *midi_clock_time -= midi_clock_target;
+      }
+    }
 
+    // Advance every beat interval that has elapsed, catching up if the pulses were late
+    let mut beat_intervals_triggered = 0;
+    while self.is_beat_interval_due() {
       // Reset and calibrate drift - https://www.youtube.com/watch?v=Gm7lcZiLOus&t=30s
-      let initial_d = self.event_trigger_time - self.event_trigger_target;
-      self.event_trigger_time = initial_d;
+      if self.event_trigger_time >= self.event_trigger_target {
+        let initial_d = self.event_trigger_time - self.event_trigger_target;
+        self.event_trigger_time = initial_d;
+      } else {
+        // Triggered early, owe the time back so the grid doesn't drift
+        self.event_trigger_debt += self.event_trigger_target - self.event_trigger_time;
+        self.event_trigger_time = Duration::default();
+      }
+
+      if self.trigger_beat_interval(state, &mut events).is_break() {
+        self.stop_with(state);
+        return events;
+      }
+      beat_intervals_triggered += 1;
+    }
+    if beat_intervals_triggered > 1 && !self.is_waiting_for_bar {
+      state.on_underrun(beat_intervals_triggered - 1);
+    }
+
+    if events.is_empty() {
+      events.push(PulseEvent::Nothing);
+    }
+    events
+  }
+
+  /// Advance the engine by a MIDI clock pulse received from an external source instead
+  /// of reading the clock. Call this once per received pulse, 24 per quarter note, to
+  /// follow the tempo of the source. The same callbacks as `pulse` are triggered.
+  ///
+  /// # Arguments
+  /// * `state` - The _trait_ `MusicTimerState` used for changes in music time callbacks.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::{music_timer_engine::MusicTimerState, music_time::MusicTime};
+  /// struct PerformanceState;
+  /// impl MusicTimerState for PerformanceState {
+  ///     fn on_beat_interval(&mut self, current_time: &MusicTime) {}
+  ///     fn on_beat(&mut self, current_time: &MusicTime) {}
+  ///     fn on_bar(&mut self, current_time: &MusicTime) {}
+  /// }
+  /// let mut performer_state = PerformanceState{};
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// // Received 0xF8
+  /// performer.on_external_clock(&mut performer_state);
+  /// ```
+  pub fn on_external_clock<TimerState: MusicTimerState>(
+    &mut self,
+    state: &mut TimerState,
+  ) -> Vec<PulseEvent> {
+    self.finish_stop(state);
+    if self.transport != Transport::Playing {
+      return vec![PulseEvent::Nothing];
+    }
+
+    let mut events = Vec::new();
+    self.start(state);
+    while self.external_clock_phase >= EXTERNAL_CLOCK_PHASE_PER_BEAT_INTERVAL {
+      self.external_clock_phase -= EXTERNAL_CLOCK_PHASE_PER_BEAT_INTERVAL;
+      if self.trigger_beat_interval(state, &mut events).is_break() {
+        self.stop_with(state);
+        return events;
+      }
+    }
+    self.external_clock_phase += u16::from(self.music_counter.time_signature().get_denominator());
+
+    if events.is_empty() {
+      events.push(PulseEvent::Nothing);
+    }
+    events
+  }
+
+  fn is_beat_interval_due(&self) -> bool {
+    match &self.humanize {
+      Some(humanize) => {
+        let (early, late) = humanize.offsets();
+        self.event_trigger_time + early >= self.event_trigger_target + late
+      }
+      None => self.event_trigger_time >= self.event_trigger_target,
+    }
+  }
+
+  fn start<TimerState: MusicTimerState>(&mut self, state: &mut TimerState) {
+    if !self.is_started && !self.is_waiting_for_bar {
+      self.is_started = true;
+      state.on_start();
+    }
+  }
+
+  /// Call `on_stop` if the performance was stopped by `stop` since the last pulse.
+  fn finish_stop<TimerState: MusicTimerState>(&mut self, state: &mut TimerState) {
+    if self.is_stop_pending {
+      self.is_stop_pending = false;
+      state.on_stop();
+    }
+  }
+
+  /// Trigger the callbacks for the current music time and advance it by a beat interval,
+  /// unless a callback breaks to stop the performance.
+  fn trigger_beat_interval<TimerState: MusicTimerState>(
+    &mut self,
+    state: &mut TimerState,
+    events: &mut Vec<PulseEvent>,
+  ) -> ControlFlow<()> {
+    // Stay silent until the start of a bar when armed by `start_on_next_bar`
+    let current_time = self.music_counter.current_time();
+    let is_bar_start = current_time.get_beat() == 1 && current_time.get_beat_interval() == 1;
+    if self.is_waiting_for_bar && is_bar_start {
+      self.is_waiting_for_bar = false;
+      self.start(state);
+    }
+    if !self.is_waiting_for_bar {
+      self.trigger_callbacks(state, events)?;
+    }
+
+    // Advance time
+    let performed_time = *self.music_counter.current_time();
+    self.music_counter.advance_beat_interval();
+    self.advance_count_in();
+
+    // Jump back to the start of the loop once its end has been performed
+    if let Some((loop_start, loop_end)) = self.loop_region {
+      if self.music_counter.current_time() > &loop_end {
+        self.music_counter.set_current_time(loop_start);
+        if !self.is_waiting_for_bar {
+          state.on_loop(&loop_start);
+        }
+      }
+    }
+
+    self.follow_tempo_map(&performed_time);
+
+    // A change of time signature takes effect from the start of a bar
+    let current_time = self.music_counter.current_time();
+    let is_bar_start = current_time.get_beat() == 1 && current_time.get_beat_interval() == 1;
+    if is_bar_start {
+      if let Some(time_signature) = self.next_time_signature.take() {
+        self.music_counter.set_time_signature(time_signature);
+      }
+    }
+
+    self.advance_tempo_ramp();
+    self.update_event_trigger_target();
+    if let Some(humanize) = &mut self.humanize {
+      humanize.advance();
+    }
+    ControlFlow::Continue(())
+  }
+
+  /// Trigger the callbacks for the current music time and store it as performed. Stops
+  /// triggering callbacks once one breaks.
+  fn trigger_callbacks<TimerState: MusicTimerState>(
+    &mut self,
+    state: &mut TimerState,
+    events: &mut Vec<PulseEvent>,
+  ) -> ControlFlow<()> {
+    let current_time = self.music_counter.current_time();
+    let first_event = events.len();
+
+    // On beat interval change
+    state.on_beat_interval_indexed(current_time, self.total_beat_intervals);
+    self.total_beat_intervals += 1;
+    events.push(PulseEvent::BeatInterval);
+    let mut flow = state.control_flow();
+
+    // On beat change
+    let is_beat_changed =
+      self.previous_music_time.get_beat() != self.music_counter.current_time().get_beat();
+    if flow.is_continue() && is_beat_changed {
+      state.on_beat(current_time);
+      events.push(PulseEvent::Beat);
+      flow = state.control_flow();
+      if flow.is_continue() {
+        let beat = current_time.get_beat();
+        let accent = if self.music_counter.time_signature().is_compound() {
+          beat % 3 == 1
+        } else {
+          beat == 1
+        };
+        state.on_metronome(current_time, accent);
+        flow = state.control_flow();
+      }
+    }
+
+    // On bar change, the bar the performance starts on is not a change
+    let is_started = self.previous_music_time.get_beat() > 0;
+    let is_bar_changed = is_started && current_time.get_bar() > self.previous_music_time.get_bar();
+    if flow.is_continue() && is_bar_changed {
+      state.on_bar(current_time);
+      events.push(PulseEvent::Bar);
+      flow = state.control_flow();
+
+      // On phrase change
+      let is_phrase_changed = self.phrase_length > 0
+        && current_time.get_bar() > 0
+        && (current_time.get_bar() - 1).is_multiple_of(self.phrase_length);
+      if flow.is_continue() && is_phrase_changed {
+        state.on_phrase(current_time);
+        flow = state.control_flow();
+      }
     }
+
+    if let Some(recording) = &mut self.recording {
+      for event in &events[first_event..] {
+        recording.push((self.performance_time, *event, *current_time));
+      }
+    }
+
+    // Store time
+    self.previous_music_time = *current_time;
+    flow
+  }
+
+  fn advance_count_in(&mut self) {
+    let is_count_in_bar_complete =
+      self.count_in_bars_remaining > 0 && self.music_counter.current_time().get_bar() > 0;
+    if is_count_in_bar_complete {
+      self.count_in_bars_remaining -= 1;
+      if self.count_in_bars_remaining > 0 {
+        self.music_counter.set_current_time(MusicTime::new(0, 1, 1));
+      }
+    }
+  }
+
+  /// Follow the tempo map to the bpm active from `performed_time`, the beat interval
+  /// whose duration is being waited for, and the time signature active at the current
+  /// music time.
+  fn follow_tempo_map(&mut self, performed_time: &MusicTime) {
+    let current_time = *self.music_counter.current_time();
+    let (bpm, time_signature) = match &self.tempo_map {
+      Some(tempo_map) => (
+        tempo_map.bpm_at(performed_time),
+        tempo_map.time_signature_at(&current_time),
+      ),
+      None => return,
+    };
+
+    if let Some(bpm) = bpm {
+      if bpm != self.bpm {
+        self.tempo_ramp = None;
+        self.bpm = bpm;
+      }
+    }
+    if let Some(time_signature) = time_signature {
+      let pending_time_signature = self
+        .next_time_signature
+        .unwrap_or(*self.music_counter.time_signature());
+      if time_signature != pending_time_signature {
+        // Validated when added to the tempo map
+        let _ = self.set_time_signature(time_signature);
+      }
+    }
+  }
+
+  fn advance_tempo_ramp(&mut self) {
+    if let Some(ramp) = &self.tempo_ramp {
+      let time_signature = self.music_counter.time_signature();
+      let current_time = self.music_counter.current_time();
+      let ramp_length = beat_intervals_between(&ramp.start_time, &ramp.end_time, time_signature);
+      let ramp_position = beat_intervals_between(&ramp.start_time, current_time, time_signature);
+
+      let is_ramp_complete = ramp_position >= ramp_length;
+      let bpm = if is_ramp_complete {
+        ramp.target_bpm
+      } else {
+        let progress = ramp_position as f32 / ramp_length as f32;
+        ramp.start_bpm + (ramp.target_bpm - ramp.start_bpm) * progress
+      };
+      if is_ramp_complete {
+        self.tempo_ramp = None;
+      }
+
+      self.bpm = bpm;
+    }
+  }
+
+  fn update_event_trigger_target(&mut self) {
+    let beat_interval_duration = self.get_beat_interval_duration();
+
+    // Swing delays the even units of the swing grid, the beat intervals of the odd units
+    // are lengthened and those of the even units shortened by the same amount so the beat
+    // duration is kept.
+    let swing_offset = beat_interval_duration.mul_f32(2.0 * self.swing - 1.0);
+    let resolution = u16::from(self.music_counter.resolution());
+    let next_beat_interval = u16::from(self.music_counter.current_time().get_beat_interval());
+    let previous_beat_interval = (next_beat_interval + resolution - 2) % resolution;
+    let is_previous_unit_odd = (previous_beat_interval / u16::from(self.swing_grid)) % 2 == 0;
+    self.event_trigger_target = if is_previous_unit_odd {
+      beat_interval_duration + swing_offset
+    } else {
+      beat_interval_duration - swing_offset
+    };
+
+    // The groove delays each beat interval from the grid by its own offset
+    if !self.groove.is_empty() {
+      let groove_length = self.groove.len();
+      let next_offset =
+        self.groove[usize::from(next_beat_interval.saturating_sub(1)) % groove_length];
+      let previous_offset = self.groove[usize::from(previous_beat_interval) % groove_length];
+      self.event_trigger_target =
+        (self.event_trigger_target + next_offset).saturating_sub(previous_offset);
+    }
+  }
+
+  /// Re-bases the start of the performance on the current time, keeping the total
+  /// time performed. Use this to recover after `try_pulse` reports reversed time.
+  pub fn rebase_start_time(&mut self) {
+    self.is_start_scheduled = false;
+    let now = self.clock.now();
+    match now.checked_sub(self.total_time) {
+      Some(start_time) => self.start_time = start_time,
+      None => {
+        self.start_time = now;
+        self.total_time = Duration::default();
+        self.previous_time = Duration::default();
+      }
+    }
+  }
+
+  /// Gets how far the performance is into the current beat interval as of the last
+  /// pulse, from `0.0` to `1.0`. Handy for interpolating visuals between beat intervals.
+  /// The phase is `0.0` for a beat interval of no duration.
+  ///
+  /// # Example
+  /// ```
+  /// use std::time::Duration;
+  /// use music_timer::{music_timer_engine::MusicTimerState, music_time::MusicTime};
+  /// struct PerformanceState;
+  /// impl MusicTimerState for PerformanceState {
+  ///     fn on_beat_interval(&mut self, current_time: &MusicTime) {}
+  ///     fn on_beat(&mut self, current_time: &MusicTime) {}
+  ///     fn on_bar(&mut self, current_time: &MusicTime) {}
+  /// }
+  /// let mut performer_state = PerformanceState{};
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.tick(Duration::default(), &mut performer_state);
+  /// assert_eq!(performer.phase(), 0.0);
+  /// ```
+  pub fn phase(&self) -> f32 {
+    // A groove can pull a beat interval all the way onto the previous one
+    if self.event_trigger_target.is_zero() {
+      return 0.0;
+    }
+    let phase = self.event_trigger_time.as_secs_f32() / self.event_trigger_target.as_secs_f32();
+    phase.clamp(0.0, 1.0)
+  }
+
+  /// Gets the time remaining until `target` is triggered at the current bpm and speed, or
+  /// zero if `target` has already been performed, saturating at `Duration::MAX`. Swing,
+  /// tempo ramps and loops are not accounted for.
+  ///
+  /// # Arguments
+  /// * `target` - The future music time.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::music_time::MusicTime;
+  /// use std::time::Duration;
+  /// let performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// assert_eq!(performer.time_until(MusicTime::new(2, 1, 1)), Duration::from_secs(2));
+  /// ```
+  pub fn time_until(&self, target: MusicTime) -> Duration {
+    let beat_intervals = beat_intervals_between(
+      self.music_counter.current_time(),
+      &target,
+      self.music_counter.time_signature(),
+    );
+    if beat_intervals < 0 {
+      return Duration::default();
+    }
+
+    let until_current = self
+      .event_trigger_target
+      .saturating_sub(self.event_trigger_time);
+    let beat_interval_duration = self.get_beat_interval_duration();
+    u32::try_from(beat_intervals)
+      .ok()
+      .and_then(|beat_intervals| beat_interval_duration.checked_mul(beat_intervals))
+      .and_then(|until_target| until_current.checked_add(until_target))
+      .unwrap_or(Duration::MAX)
+  }
+
+  /// Gets how far the performance is through the current bar as of the last pulse, from
+  /// `0.0` to `1.0`. Handy for drawing a playhead around a bar.
+  ///
+  /// # Example
+  /// ```
+  /// let performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// assert_eq!(performer.bar_progress(), 0.0);
+  /// ```
+  pub fn bar_progress(&self) -> f32 {
+    // The last beat interval performed is the one in progress
+    let performed_time = self.previous_music_time;
+    if performed_time.get_beat() == 0 {
+      return 0.0;
+    }
+
+    let resolution = f32::from(self.music_counter.resolution());
+    let beats_per_bar = f32::from(self.music_counter.time_signature().get_numerator());
+    let beat_intervals = f32::from(performed_time.get_beat() - 1) * resolution
+      + f32::from(performed_time.get_beat_interval() - 1)
+      + self.phase();
+    (beat_intervals / (beats_per_bar * resolution)).clamp(0.0, 1.0)
   }
 
   /// Gets the duration of time between beat intervals. Handy for sleeping threads.
   ///
   /// # Example
   /// ```
-  /// let mut performer = music_timer::create_performance_engine(3, 4, 155.0);
+  /// let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
   ///
   /// // We can set the delay to be half the trigger target. This will give
   /// // us a reasonable cycle speed with enough buffer to keep an accurate time.
@@ -159,20 +1426,3102 @@ impl MusicTimerEngine {
   /// ```
   ///
   pub fn get_beat_interval_duration(&self) -> Duration {
-    self.event_trigger_target
+    self
+      .music_counter
+      .beat_interval_target_frames(self.bpm * self.speed)
+  }
+
+  /// Gets the duration of time between beat intervals at the bpm of the tempo ramp as of
+  /// the last pulse, interpolated within the current beat interval. Unlike
+  /// `get_beat_interval_duration` it changes smoothly rather than once per beat interval,
+  /// handy for the sleep of an update loop during a ramp. Without a ramp they are the same.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::music_time::MusicTime;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.ramp_bpm(160.0, MusicTime::new(9, 1, 1));
+  /// let sleep_duration = performer.effective_interval_duration() / 2;
+  /// ```
+  pub fn effective_interval_duration(&self) -> Duration {
+    let ramp = match &self.tempo_ramp {
+      Some(ramp) => ramp,
+      None => return self.get_beat_interval_duration(),
+    };
+
+    // The last triggered beat interval is the one before the current time
+    let time_signature = self.music_counter.time_signature();
+    let current_time = self.music_counter.current_time();
+    let ramp_length = beat_intervals_between(&ramp.start_time, &ramp.end_time, time_signature);
+    let ramp_position = beat_intervals_between(&ramp.start_time, current_time, time_signature);
+    let position = ramp_position as f32 - 1.0 + self.phase();
+    let progress = if ramp_length > 0 {
+      (position / ramp_length as f32).clamp(0.0, 1.0)
+    } else {
+      1.0
+    };
+    let bpm = ramp.start_bpm + (ramp.target_bpm - ramp.start_bpm) * progress;
+    self
+      .music_counter
+      .beat_interval_target_frames(bpm * self.speed)
+  }
+
+  /// Gets the duration of time between beats. Handy for animating on the beat.
+  ///
+  /// # Example
+  /// ```
+  /// use std::time::Duration;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// assert_eq!(performer.get_beat_duration(), Duration::from_millis(500));
+  /// performer.set_bpm(60.0);
+  /// assert_eq!(performer.get_beat_duration(), Duration::from_secs(1));
+  /// ```
+  pub fn get_beat_duration(&self) -> Duration {
+    self.music_counter.beat_target_frames(self.bpm * self.speed)
+  }
+
+  /// Start sending MIDI clock pulses, 24 per quarter note, independent of the beat
+  /// intervals. `on_midi_start` is called and the first MIDI clock pulse is triggered on
+  /// the next pulse of the engine. Start the MIDI clock before the first pulse of the
+  /// performance to keep the pulses aligned with the beats.
+  ///
+  /// # Arguments
+  /// * `state` - The _trait_ `MusicTimerState` used for the MIDI callbacks.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::{music_timer_engine::MusicTimerState, music_time::MusicTime};
+  /// struct PerformanceState;
+  /// impl MusicTimerState for PerformanceState {
+  ///     fn on_beat_interval(&mut self, current_time: &MusicTime) {}
+  ///     fn on_beat(&mut self, current_time: &MusicTime) {}
+  ///     fn on_bar(&mut self, current_time: &MusicTime) {}
+  ///     fn on_midi_clock(&mut self) {
+  ///         // Send 0xF8 to the synth
+  ///     }
+  /// }
+  /// let mut performer_state = PerformanceState{};
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.start_midi_clock(&mut performer_state);
+  /// performer.pulse(&mut performer_state);
+  /// performer.stop_midi_clock(&mut performer_state);
+  /// ```
+  pub fn start_midi_clock<TimerState: MusicTimerState>(&mut self, state: &mut TimerState) {
+    let midi_clock_target = self
+      .music_counter
+      .midi_clock_target_frames(self.bpm * self.speed);
+    self.midi_clock_time = Some(midi_clock_target);
+    state.on_midi_start();
+  }
+
+  /// Stop sending MIDI clock pulses and call `on_midi_stop`.
+  ///
+  /// # Arguments
+  /// * `state` - The _trait_ `MusicTimerState` used for the MIDI callbacks.
+  pub fn stop_midi_clock<TimerState: MusicTimerState>(&mut self, state: &mut TimerState) {
+    if self.midi_clock_time.take().is_some() {
+      state.on_midi_stop();
+    }
+  }
+
+  /// Returns `true` if MIDI clock pulses are being sent.
+  pub fn is_midi_clock_started(&self) -> bool {
+    self.midi_clock_time.is_some()
   }
 
-  /// Gets the current music time of the performance.
+  /// Gets a human-readable summary of the state of the performance, _e.g._ for bug
+  /// reports. Use the `Debug` format of the engine for every detail.
+  ///
+  /// # Example
+  /// ```
+  /// let performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// println!("{}", performer.debug_state());
+  /// ```
+  pub fn debug_state(&self) -> String {
+    let time_signature = self.music_counter.time_signature();
+    let current_time = self.music_counter.current_time();
+    format!(
+      "{:?} at {}.{}.{} in {}/{}, {}bpm at {}x speed, {:?} of {:?} towards the next beat interval",
+      self.transport,
+      current_time.get_bar(),
+      current_time.get_beat(),
+      current_time.get_beat_interval(),
+      time_signature.get_numerator(),
+      time_signature.get_denominator(),
+      self.bpm,
+      self.speed,
+      self.event_trigger_time,
+      self.event_trigger_target,
+    )
+  }
+
+  /// Gets the time read from the clock since the start of the performance, as of the
+  /// last pulse. Time advanced with `tick` is not included.
+  pub fn elapsed(&self) -> Duration {
+    self.total_time
+  }
+
+  /// Gets the current music time of the performance. During a count in the bar is `0`.
   pub fn get_current_time(&self) -> &MusicTime {
     self.music_counter.current_time()
   }
 
-  /// Sets the current music time.
+  /// Gets the music time of the last beat interval triggered, to compare against
+  /// `get_current_time`. This is `MusicTime::new(0, 0, 0)` until the first beat interval
+  /// of the performance is triggered, and again after a `stop` or `seek`.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::music_time::MusicTime;
+  /// let performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// assert_eq!(performer.previous_time(), &MusicTime::new(0, 0, 0));
+  /// ```
+  pub fn previous_time(&self) -> &MusicTime {
+    &self.previous_music_time
+  }
+
+  /// Returns `true` if the current music time is later than `target`, so `target` has
+  /// been triggered unless it was skipped by a seek or loop.
   ///
   /// # Arguments
-  /// * `time` - The new music time to set.
-  pub fn set_music_timer(&mut self, time: MusicTime) -> &mut Self {
-    self.music_counter.set_current_time(time);
-    self
+  /// * `target` - The music time to compare against.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::music_time::MusicTime;
+  /// let performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// assert!(!performer.has_passed(&MusicTime::new(1, 1, 1)));
+  /// assert!(performer.has_passed(&MusicTime::new(0, 4, 8)));
+  /// ```
+  pub fn has_passed(&self, target: &MusicTime) -> bool {
+    self.music_counter.current_time() > target
+  }
+
+  /// Stops the performance. The music time is reset to the start of the performance,
+  /// keeping the time signature, bpm and count in, and pulses do nothing until `play`
+  /// starts it fresh. If the performance had started `on_stop` is called on the next
+  /// pulse, use `stop_with` to call it straight away.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::music_time::MusicTime;
+  /// let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
+  /// performer.set_music_timer(MusicTime::new(2, 1, 1));
+  /// performer.stop();
+  /// assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 1));
+  /// ```
+  pub fn stop(&mut self) {
+    self.transport = Transport::Stopped;
+    if self.is_started {
+      self.is_started = false;
+      self.is_stop_pending = true;
+    }
+    self.is_waiting_for_bar = false;
+    self.is_start_scheduled = false;
+    self.is_held = false;
+    self.total_time = Duration::default();
+    self.previous_time = Duration::default();
+    self.start_time = self.clock.now();
+    self.performance_time = Duration::default();
+    if let Some(time_signature) = self.next_time_signature.take() {
+      self.music_counter.set_time_signature(time_signature);
+    }
+    self.music_counter.set_current_time(MusicTime::default());
+    self.previous_music_time = MusicTime::new(0, 0, 0);
+    self.total_beat_intervals = 0;
+    self.count_in_bars_remaining = self.count_in_bars;
+    if self.count_in_bars > 0 {
+      self.music_counter.set_current_time(MusicTime::new(0, 1, 1));
+    }
+    let current_time = *self.music_counter.current_time();
+    self.follow_tempo_map(&current_time);
+    self.update_event_trigger_target();
+    self.event_trigger_time = self.event_trigger_target;
+    self.external_clock_phase = EXTERNAL_CLOCK_PHASE_PER_BEAT_INTERVAL;
+    self.reapply_output_offset();
+  }
+
+  /// Stops the performance like `stop`, calling `on_stop` straight away rather than on
+  /// the next pulse if the performance had started.
+  ///
+  /// # Arguments
+  /// * `state` - The _trait_ `MusicTimerState` used for the stop callback.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::{music_timer_engine::MusicTimerState, music_time::MusicTime};
+  /// struct PerformanceState;
+  /// impl MusicTimerState for PerformanceState {
+  ///     fn on_beat_interval(&mut self, current_time: &MusicTime) {}
+  ///     fn on_beat(&mut self, current_time: &MusicTime) {}
+  ///     fn on_bar(&mut self, current_time: &MusicTime) {}
+  /// }
+  /// let mut performer_state = PerformanceState{};
+  /// let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
+  /// performer.stop_with(&mut performer_state);
+  /// assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 1));
+  /// ```
+  pub fn stop_with<TimerState: MusicTimerState>(&mut self, state: &mut TimerState) {
+    self.stop();
+    self.finish_stop(state);
+  }
+
+  /// Gets the number of beat intervals performed since the start of the performance.
+  pub fn total_beat_intervals(&self) -> u64 {
+    self.total_beat_intervals
+  }
+
+  /// Gets the transport state of the performance. A new engine is playing, so the
+  /// first pulse starts the performance.
+  pub fn transport(&self) -> Transport {
+    self.transport
+  }
+
+  /// Play the performance, resuming from where it was paused or starting fresh if it
+  /// was stopped. The time spent paused or stopped is not performed. Resuming calls
+  /// `recalibrate`, so the next beat interval is a full beat interval away.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::music_timer_engine::Transport;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.pause();
+  /// assert_eq!(performer.transport(), Transport::Paused);
+  /// performer.play();
+  /// assert_eq!(performer.transport(), Transport::Playing);
+  /// ```
+  pub fn play(&mut self) -> &mut Self {
+    if self.transport != Transport::Playing {
+      if self.transport == Transport::Paused {
+        self.recalibrate();
+      }
+      self.transport = Transport::Playing;
+      self.rebase_start_time();
+    }
+    self
+  }
+
+  /// Pause the performance. Pulses do nothing until `play` resumes it from the same
+  /// music time. A stopped performance stays stopped.
+  pub fn pause(&mut self) -> &mut Self {
+    if self.transport == Transport::Playing {
+      self.transport = Transport::Paused;
+    }
+    self
+  }
+
+  /// Hold the performance at the current music time, like a fermata, until `release`.
+  /// Unlike `pause` the clock keeps running, so the time held is performed and shows in
+  /// `elapsed`, but no beat intervals are triggered.
+  ///
+  /// # Example
+  /// ```
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.hold();
+  /// assert!(performer.is_held());
+  /// performer.release();
+  /// assert!(!performer.is_held());
+  /// ```
+  pub fn hold(&mut self) -> &mut Self {
+    self.is_held = true;
+    self
+  }
+
+  /// Release a `hold`, the next beat interval is a full beat interval away.
+  pub fn release(&mut self) -> &mut Self {
+    if self.is_held {
+      self.is_held = false;
+      self.recalibrate();
+    }
+    self
+  }
+
+  /// Returns `true` if the performance is held by `hold`.
+  pub fn is_held(&self) -> bool {
+    self.is_held
+  }
+
+  /// Arm the performance to start on the next bar. Music time keeps advancing but no
+  /// callbacks are triggered, including `on_start`, until the performance crosses into
+  /// the first beat of a bar, which is triggered as the start of the performance.
+  /// Handy for launching loops in time with an external master clock.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::music_time::MusicTime;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.set_music_timer(MusicTime::new(1, 3, 1));
+  /// performer.start_on_next_bar();
+  /// assert!(performer.is_waiting_for_bar());
+  /// ```
+  pub fn start_on_next_bar(&mut self) -> &mut Self {
+    self.is_waiting_for_bar = true;
+    self
+  }
+
+  /// Returns `true` if the performance is armed by `start_on_next_bar` and waiting for
+  /// the start of a bar.
+  pub fn is_waiting_for_bar(&self) -> bool {
+    self.is_waiting_for_bar
+  }
+
+  /// Start recording every change in music time triggered, with the time since the start
+  /// of the performance it was triggered at. Take the recording with `take_recording`.
+  pub fn enable_recording(&mut self) -> &mut Self {
+    if self.recording.is_none() {
+      self.recording = Some(Vec::new());
+    }
+    self
+  }
+
+  /// Take the changes in music time recorded since recording was enabled or last taken.
+  /// Recording carries on afterwards. Empty if recording is not enabled.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::{music_timer_engine::{MusicTimerState, PulseEvent}, music_time::MusicTime};
+  /// use std::time::Duration;
+  /// struct PerformanceState;
+  /// impl MusicTimerState for PerformanceState {
+  ///     fn on_beat_interval(&mut self, current_time: &MusicTime) {}
+  ///     fn on_beat(&mut self, current_time: &MusicTime) {}
+  ///     fn on_bar(&mut self, current_time: &MusicTime) {}
+  /// }
+  /// let mut performer_state = PerformanceState{};
+  /// let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
+  /// performer.enable_recording();
+  /// performer.tick(Duration::default(), &mut performer_state);
+  /// let recording = performer.take_recording();
+  /// assert_eq!(recording[0], (Duration::default(), PulseEvent::BeatInterval, MusicTime::new(1, 1, 1)));
+  /// ```
+  pub fn take_recording(&mut self) -> Vec<(Duration, PulseEvent, MusicTime)> {
+    match &mut self.recording {
+      Some(recording) => std::mem::take(recording),
+      None => Vec::new(),
+    }
+  }
+
+  /// Gets the clock the engine reads time from.
+  pub fn get_clock(&self) -> &C {
+    &self.clock
+  }
+
+  /// Gets the clock the engine reads time from as mutable, _e.g._ to advance a `ManualClock`.
+  pub fn get_clock_mut(&mut self) -> &mut C {
+    &mut self.clock
+  }
+
+  /// Gets the time signature of the performance. A change of time signature made with
+  /// `set_time_signature` is returned once it has taken effect.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::time_signature::TimeSignature;
+  /// let performer = music_timer::create_performance_engine(7, 8, 120.0).unwrap();
+  /// assert_eq!(performer.get_time_signature(), &TimeSignature::new(7, 8));
+  /// ```
+  pub fn get_time_signature(&self) -> &TimeSignature {
+    self.music_counter.time_signature()
+  }
+
+  /// Gets the time signature of the performance, the same as `get_time_signature`.
+  #[deprecated(note = "use `get_time_signature`")]
+  pub fn time_signature(&self) -> &TimeSignature {
+    self.get_time_signature()
+  }
+
+  /// Set the time signature of the performance. The current bar is completed in the
+  /// current time signature and the new one applies from the next bar, or immediately
+  /// if the performance has not started.
+  ///
+  /// # Arguments
+  /// * `time_signature` - The new time signature of the performance.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::time_signature::TimeSignature;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.set_time_signature(TimeSignature::new(3, 4)).unwrap();
+  /// assert_eq!(performer.get_time_signature(), &TimeSignature::new(3, 4));
+  /// ```
+  pub fn set_time_signature(
+    &mut self,
+    time_signature: TimeSignature,
+  ) -> Result<&mut Self, TimeSignatureError> {
+    time_signature.validate()?;
+    let is_started = self.previous_music_time.get_beat() > 0;
+    if is_started {
+      self.next_time_signature = Some(time_signature);
+    } else {
+      self.next_time_signature = None;
+      self.music_counter.set_time_signature(time_signature);
+    }
+    Ok(self)
+  }
+
+  /// Count beats without ever wrapping into the next bar, _e.g._ for ambient pieces, see
+  /// `MusicTimeCounter::set_free_running`. `get_current_time` stays in the current bar
+  /// with the beat counting past the numerator, so `on_bar` is never called. A change of
+  /// time signature waits for the beat to wrap back to `1` after beat `u8::MAX`, use
+  /// `total_beat_intervals` for an endless count.
+  ///
+  /// # Arguments
+  /// * `is_free_running` - `true` to ignore the numerator, `false` to wrap into bars.
+  ///
+  /// # Example
+  /// ```
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 60.0).unwrap();
+  /// performer.set_free_running(true);
+  /// assert!(performer.is_free_running());
+  /// ```
+  pub fn set_free_running(&mut self, is_free_running: bool) -> &mut Self {
+    self.music_counter.set_free_running(is_free_running);
+    self
+  }
+
+  /// Returns `true` if the performance counts beats without wrapping into bars.
+  pub fn is_free_running(&self) -> bool {
+    self.music_counter.is_free_running()
+  }
+
+  /// Set the number of bars in a phrase, `on_phrase` is called at the start of each
+  /// phrase from bar 1, _e.g._ bars 5 and 9 for a length of 4. A length of `0` turns
+  /// phrases off, which is the default.
+  ///
+  /// # Arguments
+  /// * `bars` - The number of bars in a phrase.
+  ///
+  /// # Example
+  /// ```
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.set_phrase_length(8);
+  /// assert_eq!(performer.get_phrase_length(), 8);
+  /// ```
+  pub fn set_phrase_length(&mut self, bars: u16) -> &mut Self {
+    self.phrase_length = bars;
+    self
+  }
+
+  /// Gets the number of bars in a phrase, `0` if phrases are off.
+  pub fn get_phrase_length(&self) -> u16 {
+    self.phrase_length
+  }
+
+  /// Follow the changes of bpm and time signature of a `TempoMap`, _e.g._ of an imported
+  /// arrangement. On each beat interval the engine picks the bpm and time signature
+  /// active at the current music time, overriding `set_bpm`, `ramp_bpm` and
+  /// `set_time_signature`. A change of time signature takes effect from the start of a
+  /// bar. Before the first change of each the current bpm and time signature are kept.
+  ///
+  /// # Arguments
+  /// * `tempo_map` - The changes to follow.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::{music_time::MusicTime, tempo_map::TempoMap};
+  /// let mut tempo_map = TempoMap::new();
+  /// tempo_map
+  ///     .add_bpm(MusicTime::new(1, 1, 1), 90.0)
+  ///     .unwrap()
+  ///     .add_bpm(MusicTime::new(17, 1, 1), 120.0)
+  ///     .unwrap();
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.set_tempo_map(tempo_map);
+  /// assert_eq!(performer.get_bpm(), 90.0);
+  /// ```
+  pub fn set_tempo_map(&mut self, tempo_map: TempoMap) -> &mut Self {
+    self.tempo_map = Some(tempo_map);
+    let current_time = *self.music_counter.current_time();
+    self.follow_tempo_map(&current_time);
+    self.retarget_event_trigger();
+    self
+  }
+
+  /// Stop following the tempo map, keeping the current bpm and time signature.
+  pub fn clear_tempo_map(&mut self) -> &mut Self {
+    self.tempo_map = None;
+    self
+  }
+
+  /// Gets the tempo map being followed, if any.
+  pub fn get_tempo_map(&self) -> Option<&TempoMap> {
+    self.tempo_map.as_ref()
+  }
+
+  /// Gets the beats per minute of the performance.
+  pub fn get_bpm(&self) -> f32 {
+    self.bpm
+  }
+
+  /// Sets the beats per minute of the performance. The current music time and the time
+  /// accumulated towards the next beat interval are preserved.
+  ///
+  /// # Arguments
+  /// * `bpm` - The new beats per minute used for the performance.
+  ///
+  /// # Panics
+  /// Panics if `bpm` is not finite and greater than zero, _e.g._ a tempo slider at zero.
+  ///
+  /// # Example
+  /// ```
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// let duration_120 = performer.get_beat_interval_duration();
+  /// performer.set_bpm(240.0);
+  /// assert_eq!(performer.get_beat_interval_duration(), duration_120 / 2);
+  /// ```
+  pub fn set_bpm(&mut self, bpm: f32) -> &mut Self {
+    assert_bpm(bpm);
+    self.tempo_ramp = None;
+    self.bpm = bpm;
+    self.retarget_event_trigger();
+    self
+  }
+
+  /// Double the bpm of the performance, keeping the current music time. Reversed by
+  /// `half_time`.
+  ///
+  /// # Example
+  /// ```
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.double_time();
+  /// assert_eq!(performer.get_bpm(), 240.0);
+  /// ```
+  pub fn double_time(&mut self) -> &mut Self {
+    self.set_bpm(self.bpm * 2.0)
+  }
+
+  /// Half the bpm of the performance, keeping the current music time. Reversed by
+  /// `double_time`.
+  ///
+  /// # Example
+  /// ```
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.half_time();
+  /// assert_eq!(performer.get_bpm(), 60.0);
+  /// ```
+  pub fn half_time(&mut self) -> &mut Self {
+    self.set_bpm(self.bpm * 0.5)
+  }
+
+  /// Sets the playback speed of the performance without changing its bpm, _e.g._ `0.5`
+  /// plays at half speed by doubling the duration of the beat intervals.
+  ///
+  /// # Arguments
+  /// * `multiplier` - The playback speed, must be finite and greater than zero.
+  ///
+  /// # Example
+  /// ```
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// let duration = performer.get_beat_interval_duration();
+  /// performer.set_speed(0.5).unwrap();
+  /// assert_eq!(performer.get_bpm(), 120.0);
+  /// assert_eq!(performer.get_beat_interval_duration(), duration * 2);
+  /// ```
+  pub fn set_speed(&mut self, multiplier: f32) -> Result<&mut Self, SpeedError> {
+    if multiplier <= 0.0 || !multiplier.is_finite() {
+      return Err(SpeedError::NotPositive);
+    }
+    self.speed = multiplier;
+    self.retarget_event_trigger();
+    Ok(self)
+  }
+
+  /// Gets the playback speed of the performance.
+  pub fn get_speed(&self) -> f32 {
+    self.speed
+  }
+
+  /// Sets the swing of the performance. The odd beat intervals are lengthened and the
+  /// even beat intervals shortened so that the duration of a beat is unchanged. The swing
+  /// grid set by `set_swing_grid` is kept.
+  ///
+  /// # Arguments
+  /// * `ratio` - The portion of a pair of beat intervals given to the odd beat interval.
+  ///   `0.5` is straight and `0.66` is a triplet feel. Clamped between `0.5` and `0.75`.
+  ///
+  /// # Example
+  /// ```
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 90.0).unwrap();
+  /// performer.set_swing(0.66);
+  /// ```
+  pub fn set_swing(&mut self, ratio: f32) -> &mut Self {
+    self.swing = ratio.clamp(0.5, 0.75);
+    self.retarget_event_trigger();
+    self
+  }
+
+  /// Gets the swing ratio of the performance.
+  pub fn get_swing(&self) -> f32 {
+    self.swing
+  }
+
+  /// Sets the swing of the performance on a coarser grid than `set_swing`, _e.g._ only
+  /// the eighth notes. Each pair of `every_n_intervals` beat intervals is swung as a
+  /// pair, the beat intervals within them are evenly spaced. Music times are unchanged,
+  /// only when they are triggered. `every_n_intervals` should divide half the resolution,
+  /// a grid of `0` is treated as `1`, which is the grid of `set_swing`.
+  ///
+  /// # Arguments
+  /// * `every_n_intervals` - The number of beat intervals in a unit of the swing grid.
+  /// * `ratio` - The portion of a pair of units given to the odd unit, see `set_swing`.
+  ///
+  /// # Example
+  /// ```
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 90.0).unwrap();
+  /// // Swing the eighth notes, leaving the 16ths and 32nds within them straight
+  /// performer.set_swing_grid(4, 0.66);
+  /// ```
+  pub fn set_swing_grid(&mut self, every_n_intervals: u8, ratio: f32) -> &mut Self {
+    self.swing_grid = every_n_intervals.max(1);
+    self.set_swing(ratio)
+  }
+
+  /// Gets the number of beat intervals in a unit of the swing grid.
+  pub fn get_swing_grid(&self) -> u8 {
+    self.swing_grid
+  }
+
+  /// Set a groove template of microtiming, delaying the beat intervals of each beat from
+  /// the grid, _e.g._ to load the groove of a drum machine. The offset of a beat interval
+  /// is `offsets[(beat_interval - 1) % offsets.len()]`, so a short groove repeats within
+  /// the beat. Music times are unchanged, only when they are triggered. An empty groove
+  /// turns it off.
+  ///
+  /// # Arguments
+  /// * `offsets` - The delays of the beat intervals within a beat.
+  ///
+  /// # Example
+  /// ```
+  /// use std::time::Duration;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// // Lay back every other 32nd note
+  /// performer.set_groove(vec![Duration::default(), Duration::from_millis(6)]);
+  /// ```
+  pub fn set_groove(&mut self, offsets: Vec<Duration>) -> &mut Self {
+    self.groove = offsets;
+    self.retarget_event_trigger();
+    self
+  }
+
+  /// Gets the groove template of microtiming, empty if there is none.
+  pub fn get_groove(&self) -> &[Duration] {
+    &self.groove
+  }
+
+  fn retarget_event_trigger(&mut self) {
+    let previous_target = self.event_trigger_target;
+    self.update_event_trigger_target();
+
+    // An interval that was already due must trigger once on the next pulse, keeping
+    // only the time that overshot it
+    let is_beat_interval_due = self.event_trigger_time >= previous_target;
+    if is_beat_interval_due {
+      let overshoot = self.event_trigger_time - previous_target;
+      self.event_trigger_time = self.event_trigger_target + overshoot;
+    }
+  }
+
+  /// Set the latency of the audio output. Beat intervals are triggered `latency` ahead of
+  /// their nominal time to compensate, while still reporting their nominal music time.
+  /// This replaces any output delay.
+  ///
+  /// # Arguments
+  /// * `latency` - The duration to trigger beat intervals early by.
+  ///
+  /// # Example
+  /// ```
+  /// use std::time::Duration;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.set_output_latency(Duration::from_millis(10));
+  /// assert_eq!(performer.get_output_latency(), Duration::from_millis(10));
+  /// ```
+  pub fn set_output_latency(&mut self, latency: Duration) -> &mut Self {
+    self.shift_event_trigger_time(latency, Duration::default());
+    self
+  }
+
+  /// Gets the latency beat intervals are triggered ahead by.
+  pub fn get_output_latency(&self) -> Duration {
+    self.output_latency
+  }
+
+  /// Set the delay of the audio output, the opposite of `set_output_latency`. Beat intervals
+  /// are triggered `delay` after their nominal time. This replaces any output latency.
+  ///
+  /// # Arguments
+  /// * `delay` - The duration to trigger beat intervals late by.
+  ///
+  /// # Example
+  /// ```
+  /// use std::time::Duration;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.set_output_delay(Duration::from_millis(10));
+  /// assert_eq!(performer.get_output_delay(), Duration::from_millis(10));
+  /// ```
+  pub fn set_output_delay(&mut self, delay: Duration) -> &mut Self {
+    self.shift_event_trigger_time(Duration::default(), delay);
+    self
+  }
+
+  /// Gets the delay beat intervals are triggered behind by.
+  pub fn get_output_delay(&self) -> Duration {
+    self.output_delay
+  }
+
+  /// Move the time towards the next beat interval from the current output offset to a new one.
+  fn shift_event_trigger_time(&mut self, latency: Duration, delay: Duration) {
+    let ahead = latency + self.output_delay;
+    let behind = self.output_latency + delay;
+    self.output_latency = latency;
+    self.output_delay = delay;
+
+    if ahead >= behind {
+      // Pay back any delay still to come before moving ahead
+      let shift = ahead - behind;
+      let repaid = shift.min(self.event_trigger_debt);
+      self.event_trigger_debt -= repaid;
+      self.event_trigger_time += shift - repaid;
+    } else {
+      // Delay what can't be taken from the time already elapsed
+      let shift = behind - ahead;
+      let taken = shift.min(self.event_trigger_time);
+      self.event_trigger_time -= taken;
+      self.event_trigger_debt += shift - taken;
+    }
+  }
+
+  /// Humanize the performance by triggering each beat interval up to `max_jitter` early
+  /// or late, drawn from a pseudo-random sequence. The jitter is around the grid so
+  /// it never accumulates. A `max_jitter` of zero turns humanizing off.
+  ///
+  /// # Arguments
+  /// * `max_jitter` - The most a beat interval is triggered early or late by.
+  /// * `seed` - The seed of the pseudo-random sequence, the same seed repeats the same jitter.
+  ///
+  /// # Example
+  /// ```
+  /// use std::time::Duration;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.set_humanize(Duration::from_millis(8), 42);
+  /// ```
+  pub fn set_humanize(&mut self, max_jitter: Duration, seed: u64) -> &mut Self {
+    self.humanize = if max_jitter > Duration::default() {
+      Some(Humanize::new(max_jitter, seed))
+    } else {
+      None
+    };
+    self
+  }
+
+  /// Apply the output offset again after the time towards the next beat interval was reset.
+  fn reapply_output_offset(&mut self) {
+    let (latency, delay) = (self.output_latency, self.output_delay);
+    self.output_latency = Duration::default();
+    self.output_delay = Duration::default();
+    self.event_trigger_debt = Duration::default();
+    self.shift_event_trigger_time(latency, delay);
+  }
+
+  /// Gradually change the bpm of the performance. The bpm is linearly interpolated on each
+  /// beat interval from the current music time, reaching `target_bpm` exactly at `over`
+  /// and holding it from then on. Calling `set_bpm` cancels the ramp.
+  ///
+  /// # Arguments
+  /// * `target_bpm` - The beats per minute at the end of the ramp.
+  /// * `over` - The music time the ramp ends at.
+  ///
+  /// # Panics
+  /// Panics if `target_bpm` is not finite and greater than zero.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::music_time::MusicTime;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// // Accelerando over 8 bars
+  /// performer.ramp_bpm(160.0, MusicTime::new(9, 1, 1));
+  /// ```
+  pub fn ramp_bpm(&mut self, target_bpm: f32, over: MusicTime) -> &mut Self {
+    assert_bpm(target_bpm);
+    self.tempo_ramp = Some(TempoRamp {
+      start_bpm: self.bpm,
+      target_bpm,
+      start_time: *self.music_counter.current_time(),
+      end_time: over,
+    });
+    self.advance_tempo_ramp();
+    self
+  }
+
+  /// Loop a region of the performance. Once `end` has been performed the performance
+  /// jumps back to `start` and `on_loop` is called. Timing is kept tight across the jump.
+  ///
+  /// # Arguments
+  /// * `start` - The first music time of the loop.
+  /// * `end` - The last music time of the loop, inclusive.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::music_time::MusicTime;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// // Loop bars 5 to 8
+  /// performer
+  ///     .set_loop(MusicTime::new(5, 1, 1), MusicTime::new(8, 4, 8))
+  ///     .unwrap();
+  /// ```
+  pub fn set_loop(&mut self, start: MusicTime, end: MusicTime) -> Result<&mut Self, LoopError> {
+    if end < start {
+      return Err(LoopError::TooShort);
+    }
+    self.loop_region = Some((start, end));
+    Ok(self)
+  }
+
+  /// Stop looping the performance.
+  pub fn clear_loop(&mut self) -> &mut Self {
+    self.loop_region = None;
+    self
+  }
+
+  /// Gets the start and end of the loop region, if any.
+  pub fn get_loop(&self) -> Option<&(MusicTime, MusicTime)> {
+    self.loop_region.as_ref()
+  }
+
+  /// Align this performance to a leader, _e.g._ for polymetric pieces with an engine for
+  /// each meter. The start time, bpm, speed and the time towards the next beat interval
+  /// are copied so both trigger their beat intervals together, while keeping their own
+  /// music time and time signature. Any tempo ramp is cancelled. Both clocks should read
+  /// the same time.
+  ///
+  /// # Arguments
+  /// * `leader` - The engine to follow.
+  ///
+  /// # Example
+  /// ```
+  /// let leader = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// let mut follower = music_timer::create_performance_engine(3, 4, 90.0).unwrap();
+  /// follower.sync_to(&leader);
+  /// assert_eq!(follower.get_bpm(), 120.0);
+  /// ```
+  pub fn sync_to<L: Clock>(&mut self, leader: &MusicTimerEngine<L>) -> &mut Self {
+    self.start_time = leader.start_time;
+    self.total_time = leader.total_time;
+    self.previous_time = leader.previous_time;
+    self.performance_time = leader.performance_time;
+    self.tempo_ramp = None;
+    self.bpm = leader.bpm;
+    self.speed = leader.speed;
+    self.update_event_trigger_target();
+    self.event_trigger_time = leader.event_trigger_time;
+    self.event_trigger_debt = leader.event_trigger_debt;
+    self
+  }
+
+  /// Nudge the phase of the performance without seeking, so the next beat interval is
+  /// triggered a little sooner or later. Repeated small nudges phase lock the performance
+  /// to an external pulse, _e.g._ a live drummer. A nudge never moves past the next beat
+  /// interval or back past the previous one.
+  ///
+  /// # Arguments
+  /// * `amount` - The duration to move the next beat interval by.
+  /// * `forward` - `true` to trigger the next beat interval sooner, `false` for later.
+  ///
+  /// # Example
+  /// ```
+  /// use std::time::Duration;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// // The drummer is ahead, catch up
+  /// performer.nudge(Duration::from_millis(2), true);
+  /// ```
+  pub fn nudge(&mut self, amount: Duration, forward: bool) -> &mut Self {
+    self.event_trigger_time = if forward {
+      let due_time = self.event_trigger_target.max(self.event_trigger_time);
+      (self.event_trigger_time + amount).min(due_time)
+    } else {
+      self.event_trigger_time.saturating_sub(amount)
+    };
+    self
+  }
+
+  /// Seek the performance to a music time. Unlike `set_music_timer` the time towards the
+  /// next beat interval is reset, so the performance resumes from `time` one beat interval
+  /// later as if it had started there. Following an external clock, `time` is performed on
+  /// the next `on_external_clock`.
+  ///
+  /// # Arguments
+  /// * `time` - The music time to resume the performance from.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::music_time::MusicTime;
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.seek(MusicTime::new(3, 1, 1));
+  /// assert_eq!(performer.get_current_time(), &MusicTime::new(3, 1, 1));
+  /// ```
+  pub fn seek(&mut self, time: MusicTime) -> &mut Self {
+    self.music_counter.set_current_time(time);
+    self.previous_music_time = MusicTime::new(0, 0, 0);
+    self.count_in_bars_remaining = 0;
+    self.external_clock_phase = EXTERNAL_CLOCK_PHASE_PER_BEAT_INTERVAL;
+    self.follow_tempo_map(&time);
+    self.update_event_trigger_target();
+    self.recalibrate();
+    self
+  }
+
+  /// Restart the timing of the performance cleanly from now, so the next beat interval
+  /// is a full beat interval away. The drift calibration carries lateness from pulse to
+  /// pulse, which after a jump can cause a burst of catching up. Called by `seek` and
+  /// when `play` resumes a paused performance.
+  ///
+  /// # Example
+  /// ```
+  /// let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+  /// performer.recalibrate();
+  /// ```
+  pub fn recalibrate(&mut self) -> &mut Self {
+    self.event_trigger_time = Duration::default();
+    self.reapply_output_offset();
+    self
+  }
+
+  /// Sets the current music time.
+  ///
+  /// # Arguments
+  /// * `time` - The new music time to set.
+  pub fn set_music_timer(&mut self, time: MusicTime) -> &mut Self {
+    self.music_counter.set_current_time(time);
+    self.retarget_event_trigger();
+    self
+  }
+}
+
+impl MusicTimerEngine<ManualClock> {
+  /// Replays pulses on the `ManualClock` and gets how far the last beat interval triggered
+  /// was from its ideal time, so timing can be checked without sleeping in real time. The
+  /// engine is pulsed once at the current time of its clock, then again after advancing
+  /// the clock by each of `ticks`, with the callbacks ignored. The ideal time of each beat
+  /// interval is a whole number of `get_beat_interval_duration` after the first one
+  /// triggered, so use a constant bpm without swing, groove or humanize. Without drift the
+  /// error stays below the longest tick, as a beat interval can only trigger on a pulse.
+  ///
+  /// # Arguments
+  /// * `ticks` - The durations between pulses.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::{
+  ///     clock::ManualClock, music_timer_engine::MusicTimerEngine, time_signature::TimeSignature,
+  /// };
+  /// use std::time::Duration;
+  /// let mut performer =
+  ///     MusicTimerEngine::with_clock(TimeSignature::new(4, 4), 120.0, ManualClock::new());
+  /// let ticks = [Duration::from_millis(10); 400];
+  /// assert!(performer.verify_timing(&ticks) < Duration::from_millis(10));
+  /// ```
+  pub fn verify_timing(&mut self, ticks: &[Duration]) -> Duration {
+    let beat_interval_duration = self.get_beat_interval_duration();
+    let mut first_trigger_time = None;
+    let mut beat_intervals = 0u32;
+    let mut error = Duration::default();
+
+    let pulse_times = std::iter::once(Duration::default()).chain(ticks.iter().copied());
+    for tick in pulse_times {
+      self.clock.advance(tick);
+      let now = self.clock.now();
+      let triggered = self
+        .pulse(&mut MusicTimerListeners(&mut []))
+        .iter()
+        .filter(|event| **event == PulseEvent::BeatInterval)
+        .count() as u32;
+      if triggered == 0 {
+        continue;
+      }
+
+      // Catching up triggers several beat intervals on the same pulse, the last is the latest
+      let first_trigger_time = *first_trigger_time.get_or_insert(now);
+      beat_intervals += triggered;
+      let ideal_time = first_trigger_time + beat_interval_duration * (beat_intervals - 1);
+      error = now.abs_diff(ideal_time);
+    }
+    error
+  }
+}
+
+/// Builder of a `MusicTimerEngine`, for setting up a performance by name rather than
+/// by position. Defaults to 4/4 at 120bpm.
+#[derive(Clone, Debug)]
+pub struct MusicTimerEngineBuilder {
+  time_signature: TimeSignature,
+  bpm: f32,
+  swing: f32,
+  loop_region: Option<(MusicTime, MusicTime)>,
+  count_in_bars: u16,
+}
+
+impl MusicTimerEngineBuilder {
+  /// Create a new `MusicTimerEngineBuilder`.
+  ///
+  /// # Example
+  /// ```
+  /// use music_timer::{
+  ///     music_time::MusicTime, music_timer_engine::MusicTimerEngineBuilder,
+  ///     time_signature::TimeSignature,
+  /// };
+  /// let performer = MusicTimerEngineBuilder::new()
+  ///     .time_signature(TimeSignature::new(3, 4))
+  ///     .bpm(155.0)
+  ///     .swing(0.66)
+  ///     .loop_region(MusicTime::new(1, 1, 1), MusicTime::new(4, 3, 8))
+  ///     .count_in(1)
+  ///     .build()
+  ///     .unwrap();
+  /// ```
+  pub fn new() -> Self {
+    MusicTimerEngineBuilder::default()
+  }
+
+  /// Set the time signature of the performance.
+  ///
+  /// # Arguments
+  /// * `time_signature` - The time signature for the performance.
+  pub fn time_signature(mut self, time_signature: TimeSignature) -> Self {
+    self.time_signature = time_signature;
+    self
+  }
+
+  /// Set the bpm of the performance.
+  ///
+  /// # Arguments
+  /// * `bpm` - The beats per minute used for the performance.
+  pub fn bpm(mut self, bpm: f32) -> Self {
+    self.bpm = bpm;
+    self
+  }
+
+  /// Set the swing ratio of the performance, see `MusicTimerEngine::set_swing`. Unlike
+  /// `set_swing` it is not clamped, `build` returns an error if it is out of range.
+  ///
+  /// # Arguments
+  /// * `ratio` - The swing ratio, from `0.5` to `0.75`.
+  pub fn swing(mut self, ratio: f32) -> Self {
+    self.swing = ratio;
+    self
+  }
+
+  /// Set the loop region of the performance, see `MusicTimerEngine::set_loop`.
+  ///
+  /// # Arguments
+  /// * `start` - The music time the loop jumps back to.
+  /// * `end` - The last music time performed before jumping back.
+  pub fn loop_region(mut self, start: MusicTime, end: MusicTime) -> Self {
+    self.loop_region = Some((start, end));
+    self
+  }
+
+  /// Set the bars to count in, see `MusicTimerEngine::with_count_in`.
+  ///
+  /// # Arguments
+  /// * `bars` - The number of bars to count in.
+  pub fn count_in(mut self, bars: u16) -> Self {
+    self.count_in_bars = bars;
+    self
+  }
+
+  /// Build the `MusicTimerEngine`, returning an error if the time signature, bpm, swing
+  /// ratio or loop region is invalid.
+  pub fn build(self) -> Result<MusicTimerEngine, BuildError> {
+    self.time_signature.validate()?;
+    validate_bpm(self.bpm)?;
+    validate_swing(self.swing)?;
+    let mut engine =
+      MusicTimerEngine::new(self.time_signature, self.bpm).with_count_in(self.count_in_bars);
+    engine.set_swing(self.swing);
+    if let Some((start, end)) = self.loop_region {
+      engine.set_loop(start, end)?;
+    }
+    Ok(engine)
+  }
+}
+
+impl Default for MusicTimerEngineBuilder {
+  /// Default is 4/4 at 120bpm without swing, loop or count in.
+  fn default() -> Self {
+    MusicTimerEngineBuilder {
+      time_signature: TimeSignature::default(),
+      bpm: 120.0,
+      swing: 0.5,
+      loop_region: None,
+      count_in_bars: 0,
+    }
+  }
+}
+
+mod tests {
+  use crate::{music_time::MusicTime, music_timer_engine::MusicTimerState};
+
+  struct PerformanceState {
+    beat_intervals: Vec<MusicTime>,
+    beats: Vec<MusicTime>,
+    bars: Vec<MusicTime>,
+    metronome: Vec<bool>,
+    loops: Vec<MusicTime>,
+    midi_clocks: usize,
+    midi_clocks_on_beat: Vec<usize>,
+    midi_transport: Vec<bool>,
+    transport: Vec<bool>,
+    underruns: Vec<u32>,
+  }
+
+  impl PerformanceState {
+    fn new() -> Self {
+      PerformanceState {
+        beat_intervals: Vec::new(),
+        beats: Vec::new(),
+        bars: Vec::new(),
+        metronome: Vec::new(),
+        loops: Vec::new(),
+        midi_clocks: 0,
+        midi_clocks_on_beat: Vec::new(),
+        midi_transport: Vec::new(),
+        transport: Vec::new(),
+        underruns: Vec::new(),
+      }
+    }
+  }
+
+  impl MusicTimerState for PerformanceState {
+    fn on_beat_interval(&mut self, current_time: &MusicTime) {
+      self.beat_intervals.push(*current_time);
+    }
+    fn on_beat(&mut self, current_time: &MusicTime) {
+      self.beats.push(*current_time);
+      self.midi_clocks_on_beat.push(self.midi_clocks);
+    }
+    fn on_bar(&mut self, current_time: &MusicTime) {
+      self.bars.push(*current_time);
+    }
+    fn on_metronome(&mut self, _current_time: &MusicTime, accent: bool) {
+      self.metronome.push(accent);
+    }
+    fn on_loop(&mut self, current_time: &MusicTime) {
+      self.loops.push(*current_time);
+    }
+    fn on_midi_clock(&mut self) {
+      self.midi_clocks += 1;
+    }
+    fn on_midi_start(&mut self) {
+      self.midi_transport.push(true);
+    }
+    fn on_midi_stop(&mut self) {
+      self.midi_transport.push(false);
+    }
+    fn on_start(&mut self) {
+      assert!(self.beat_intervals.is_empty());
+      self.transport.push(true);
+    }
+    fn on_stop(&mut self) {
+      self.transport.push(false);
+    }
+    fn on_underrun(&mut self, missed: u32) {
+      self.underruns.push(missed);
+    }
+  }
+
+  #[test]
+  fn test_try_pulse_time_reversed() {
+    use crate::{
+      clock::ManualClock,
+      music_timer_engine::{MusicTimerEngine, TimeError},
+      time_signature::TimeSignature,
+    };
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut clock = ManualClock::new();
+    clock.set(Duration::from_secs(60));
+    let mut performer = MusicTimerEngine::with_clock(TimeSignature::new(4, 4), 120.0, clock);
+    assert!(performer.try_pulse(&mut performer_state).is_ok());
+    assert_eq!(performer_state.beat_intervals.len(), 1);
+
+    // Step the clock backwards
+    performer.get_clock_mut().set(Duration::from_secs(30));
+    assert_eq!(
+      performer.try_pulse(&mut performer_state),
+      Err(TimeError::TimeReversed)
+    );
+    assert_eq!(performer_state.beat_intervals.len(), 1);
+
+    performer.rebase_start_time();
+    let interval_duration = performer.get_beat_interval_duration();
+    performer.get_clock_mut().advance(interval_duration);
+    assert!(performer.try_pulse(&mut performer_state).is_ok());
+    assert_eq!(performer_state.beat_intervals.len(), 2);
+    assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 3));
+  }
+
+  #[test]
+  fn test_manual_clock_bar() {
+    use crate::{
+      clock::ManualClock, music_timer_engine::MusicTimerEngine, time_signature::TimeSignature,
+    };
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(3, 4), 155.0, ManualClock::new());
+    let interval_duration = performer.get_beat_interval_duration();
+
+    // A full bar of 3/4 and the downbeat of the next
+    performer.pulse(&mut performer_state);
+    for _ in 0..8 * 3 {
+      performer.get_clock_mut().advance(interval_duration);
+      performer.pulse(&mut performer_state);
+    }
+
+    assert_eq!(performer_state.beat_intervals.len(), 8 * 3 + 1);
+    assert_eq!(performer_state.beat_intervals[0], MusicTime::new(1, 1, 1));
+    assert_eq!(performer_state.beat_intervals[8], MusicTime::new(1, 2, 1));
+    assert_eq!(performer_state.beat_intervals[23], MusicTime::new(1, 3, 8));
+    assert_eq!(
+      performer_state.beats,
+      vec![
+        MusicTime::new(1, 1, 1),
+        MusicTime::new(1, 2, 1),
+        MusicTime::new(1, 3, 1),
+        MusicTime::new(2, 1, 1),
+      ]
+    );
+    assert_eq!(performer_state.bars, vec![MusicTime::new(2, 1, 1)]);
+  }
+
+  #[test]
+  fn test_tick() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    assert_eq!(
+      performer.get_beat_interval_duration(),
+      Duration::from_micros(62500)
+    );
+
+    // Two beats of 120bpm
+    for _ in 0..100 {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+
+    assert_eq!(performer_state.beat_intervals.len(), 8 * 2 + 1);
+    assert_eq!(
+      performer_state.beats,
+      vec![
+        MusicTime::new(1, 1, 1),
+        MusicTime::new(1, 2, 1),
+        MusicTime::new(1, 3, 1),
+      ]
+    );
+    assert_eq!(performer.get_current_time(), &MusicTime::new(1, 3, 2));
+  }
+
+  #[test]
+  fn test_pulse_all() {
+    use crate::{
+      clock::ManualClock, music_timer_engine::MusicTimerEngine, time_signature::TimeSignature,
+    };
+
+    let mut audio_state = PerformanceState::new();
+    let mut lighting_state = PerformanceState::new();
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(4, 4), 120.0, ManualClock::new());
+    let interval_duration = performer.get_beat_interval_duration();
+
+    performer.pulse_all(&mut [&mut audio_state, &mut lighting_state]);
+    for _ in 0..9 {
+      performer.get_clock_mut().advance(interval_duration);
+      performer.pulse_all(&mut [&mut audio_state, &mut lighting_state]);
+    }
+
+    assert_eq!(audio_state.beat_intervals.len(), 10);
+    assert_eq!(lighting_state.beat_intervals.len(), 10);
+    assert_eq!(audio_state.beat_intervals, lighting_state.beat_intervals);
+    assert_eq!(
+      audio_state.beats,
+      vec![MusicTime::new(1, 1, 1), MusicTime::new(1, 2, 1)]
+    );
+    assert_eq!(audio_state.beats, lighting_state.beats);
+  }
+
+  #[test]
+  fn test_ramp_bpm() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    performer.ramp_bpm(160.0, MusicTime::new(9, 1, 1));
+    assert_eq!(performer.get_bpm(), 120.0);
+
+    while performer.get_current_time() < &MusicTime::new(5, 1, 1) {
+      performer.tick(Duration::from_millis(1), &mut performer_state);
+    }
+    assert_eq!(performer.get_bpm(), 140.0);
+
+    while performer.get_current_time() < &MusicTime::new(9, 1, 1) {
+      performer.tick(Duration::from_millis(1), &mut performer_state);
+    }
+    assert_eq!(performer.get_bpm(), 160.0);
+
+    while performer.get_current_time() < &MusicTime::new(10, 1, 1) {
+      performer.tick(Duration::from_millis(1), &mut performer_state);
+    }
+    assert_eq!(performer.get_bpm(), 160.0);
+  }
+
+  #[test]
+  fn test_swing_grid() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    performer.set_swing_grid(4, 0.75);
+    assert_eq!(performer.get_swing_grid(), 4);
+
+    // Record the microsecond each beat interval is triggered on
+    let mut trigger_times = Vec::new();
+    for microsecond in 0..1000001 {
+      let count = performer_state.beat_intervals.len();
+      performer.tick(Duration::from_micros(1), &mut performer_state);
+      if performer_state.beat_intervals.len() > count {
+        trigger_times.push(microsecond);
+      }
+    }
+
+    // Within a couple of microseconds of rounding
+    let assert_near = |duration: i32, expected: i32| {
+      assert!(
+        (duration - expected).abs() <= 2,
+        "{} != {}",
+        duration,
+        expected
+      );
+    };
+
+    // The eighth notes are swung, straight they would be 250ms apart
+    assert_eq!(performer_state.beat_intervals[4], MusicTime::new(1, 1, 5));
+    assert_near(trigger_times[4] - trigger_times[0], 375000);
+    assert_near(trigger_times[8] - trigger_times[4], 125000);
+
+    // The 32nds within them are evenly spaced
+    for beat_interval in 0..3 {
+      let odd = trigger_times[beat_interval + 1] - trigger_times[beat_interval];
+      let even = trigger_times[beat_interval + 5] - trigger_times[beat_interval + 4];
+      assert_near(odd, 93750);
+      assert_near(even, 31250);
+    }
+
+    // Beats keep their duration and labels
+    assert_eq!(performer_state.beat_intervals[8], MusicTime::new(1, 2, 1));
+    assert_eq!(performer_state.beat_intervals[16], MusicTime::new(1, 3, 1));
+    assert_near(trigger_times[8] - trigger_times[0], 500000);
+    assert_near(trigger_times[16] - trigger_times[8], 500000);
+  }
+
+  #[test]
+  fn test_swing() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    performer.set_swing(0.66);
+
+    // Record the millisecond each beat interval is triggered on
+    let mut trigger_times = Vec::new();
+    for millisecond in 0..1001 {
+      let count = performer_state.beat_intervals.len();
+      performer.tick(Duration::from_millis(1), &mut performer_state);
+      if performer_state.beat_intervals.len() > count {
+        trigger_times.push(millisecond);
+      }
+    }
+
+    let first_duration = trigger_times[1] - trigger_times[0];
+    let second_duration = trigger_times[2] - trigger_times[1];
+    assert!(first_duration > second_duration);
+    let swing = first_duration as f32 / (first_duration + second_duration) as f32;
+    assert!((swing - 0.66).abs() < 0.01);
+
+    // Beats keep their duration
+    assert_eq!(performer_state.beat_intervals[8], MusicTime::new(1, 2, 1));
+    assert_eq!(performer_state.beat_intervals[16], MusicTime::new(1, 3, 1));
+    assert_eq!(trigger_times[16] - trigger_times[8], 500);
+    assert_eq!(
+      performer_state.beats,
+      vec![
+        MusicTime::new(1, 1, 1),
+        MusicTime::new(1, 2, 1),
+        MusicTime::new(1, 3, 1),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_metronome() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+
+    // One bar of 4/4
+    while performer.get_current_time() < &MusicTime::new(2, 1, 1) {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+
+    assert_eq!(performer_state.metronome, vec![true, false, false, false]);
+  }
+
+  #[test]
+  fn test_metronome_compound() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(6, 8, 120.0).unwrap();
+
+    // One bar of 6/8, accented in two groups of three
+    while performer.get_current_time() < &MusicTime::new(2, 1, 1) {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+
+    assert_eq!(
+      performer_state.metronome,
+      vec![true, false, false, true, false, false]
+    );
+  }
+
+  #[test]
+  fn test_count_in() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0)
+      .unwrap()
+      .with_count_in(1);
+    assert_eq!(performer.get_current_time(), &MusicTime::new(0, 1, 1));
+
+    while performer.get_current_time() < &MusicTime::new(1, 2, 1) {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+
+    assert_eq!(
+      performer_state.beats,
+      vec![
+        MusicTime::new(0, 1, 1),
+        MusicTime::new(0, 2, 1),
+        MusicTime::new(0, 3, 1),
+        MusicTime::new(0, 4, 1),
+        MusicTime::new(1, 1, 1),
+      ]
+    );
+    assert_eq!(
+      performer_state.metronome,
+      vec![true, false, false, false, true]
+    );
+    assert_eq!(performer_state.bars, vec![MusicTime::new(1, 1, 1)]);
+
+    // Two bars repeat the count in bar
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(3, 4, 120.0)
+      .unwrap()
+      .with_count_in(2);
+    while performer.get_current_time() < &MusicTime::new(1, 1, 1) {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+    assert_eq!(performer_state.beats.len(), 3 * 2);
+    assert_eq!(
+      performer_state
+        .metronome
+        .iter()
+        .filter(|accent| **accent)
+        .count(),
+      2
+    );
+    assert!(performer_state.bars.is_empty());
+  }
+
+  #[test]
+  fn test_loop() {
+    use crate::music_timer_engine::LoopError;
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    assert_eq!(
+      performer
+        .set_loop(MusicTime::new(2, 1, 1), MusicTime::new(1, 4, 8))
+        .err(),
+      Some(LoopError::TooShort)
+    );
+    assert!(performer.get_loop().is_none());
+    performer
+      .set_loop(MusicTime::new(1, 1, 1), MusicTime::new(1, 4, 8))
+      .unwrap();
+
+    // Perform the bar and loop it twice
+    while performer_state.loops.len() < 2 {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+
+    assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 1));
+    assert_eq!(performer_state.beat_intervals.len(), 8 * 4 * 2);
+    assert_eq!(
+      performer_state.beat_intervals[8 * 4],
+      MusicTime::new(1, 1, 1)
+    );
+    assert_eq!(
+      performer_state.beat_intervals[8 * 4 * 2 - 1],
+      MusicTime::new(1, 4, 8)
+    );
+    assert_eq!(performer_state.metronome.iter().filter(|a| **a).count(), 2);
+    assert!(performer_state.bars.is_empty());
+
+    performer.clear_loop();
+    while performer.get_current_time() < &MusicTime::new(2, 2, 1) {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+    assert_eq!(performer_state.loops.len(), 2);
+    assert_eq!(performer_state.bars, vec![MusicTime::new(2, 1, 1)]);
+  }
+
+  #[test]
+  fn test_seek() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    for _ in 0..10 {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+    assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 3));
+
+    let mut performer_state = PerformanceState::new();
+    performer.seek(MusicTime::new(3, 1, 1));
+    let interval_duration = performer.get_beat_interval_duration();
+    performer.tick(interval_duration / 2, &mut performer_state);
+    assert!(performer_state.beat_intervals.is_empty());
+
+    performer.tick(interval_duration / 2, &mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(3, 1, 1)]
+    );
+    assert_eq!(performer_state.beats, vec![MusicTime::new(3, 1, 1)]);
+    assert!(performer_state.bars.is_empty());
+  }
+
+  #[test]
+  fn test_set_speed() {
+    use crate::music_timer_engine::SpeedError;
+    use std::time::Duration;
+
+    let mut baseline_state = PerformanceState::new();
+    let mut baseline = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    assert_eq!(
+      performer.set_speed(0.0).err(),
+      Some(SpeedError::NotPositive)
+    );
+    assert_eq!(
+      performer.set_speed(-1.0).err(),
+      Some(SpeedError::NotPositive)
+    );
+    performer.set_speed(2.0).unwrap();
+    assert_eq!(performer.get_bpm(), 120.0);
+
+    for _ in 0..100 {
+      baseline.tick(Duration::from_millis(10), &mut baseline_state);
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+
+    assert_eq!(baseline_state.beat_intervals.len(), 8 * 2 + 1);
+    assert_eq!(performer_state.beat_intervals.len(), 8 * 4 + 1);
+  }
+
+  #[test]
+  fn test_pulse_events() {
+    use crate::{
+      clock::ManualClock,
+      music_timer_engine::{MusicTimerEngine, PulseEvent},
+      time_signature::TimeSignature,
+    };
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(2, 4), 120.0, ManualClock::new());
+    let interval_duration = performer.get_beat_interval_duration();
+
+    assert_eq!(
+      performer.pulse(&mut performer_state),
+      vec![PulseEvent::BeatInterval, PulseEvent::Beat]
+    );
+
+    performer.get_clock_mut().advance(interval_duration / 2);
+    assert_eq!(
+      performer.pulse(&mut performer_state),
+      vec![PulseEvent::Nothing]
+    );
+
+    performer.get_clock_mut().advance(interval_duration / 2);
+    assert_eq!(
+      performer.pulse(&mut performer_state),
+      vec![PulseEvent::BeatInterval]
+    );
+
+    // Catch up to the next beat
+    performer.get_clock_mut().advance(interval_duration * 7);
+    let events = performer.pulse(&mut performer_state);
+    assert_eq!(events.len(), 7 + 1);
+    assert_eq!(
+      &events[5..],
+      &[
+        PulseEvent::BeatInterval,
+        PulseEvent::BeatInterval,
+        PulseEvent::Beat,
+      ]
+    );
+
+    // Catch up to the next bar
+    performer.get_clock_mut().advance(interval_duration * 8);
+    let events = performer.pulse(&mut performer_state);
+    assert_eq!(events.len(), 8 + 2);
+    assert_eq!(
+      &events[7..],
+      &[PulseEvent::BeatInterval, PulseEvent::Beat, PulseEvent::Bar]
+    );
+  }
+
+  #[test]
+  fn test_pulse_closures() {
+    use crate::{
+      clock::ManualClock, music_timer_engine::MusicTimerEngine, time_signature::TimeSignature,
+    };
+    use std::sync::{
+      atomic::{AtomicUsize, Ordering},
+      Arc,
+    };
+
+    let count_beat_intervals = Arc::new(AtomicUsize::new(0));
+    let count_bars = Arc::new(AtomicUsize::new(0));
+    let counter = count_beat_intervals.clone();
+    let bar_counter = count_bars.clone();
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(4, 4), 120.0, ManualClock::new())
+        .on_beat_interval(move |_| {
+          counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .on_bar(move |_| {
+          bar_counter.fetch_add(1, Ordering::SeqCst);
+        });
+    let interval_duration = performer.get_beat_interval_duration();
+
+    performer.pulse_closures();
+    for _ in 0..8 * 4 {
+      performer.get_clock_mut().advance(interval_duration);
+      performer.pulse_closures();
+    }
+
+    assert_eq!(count_beat_intervals.load(Ordering::SeqCst), 8 * 4 + 1);
+    assert_eq!(count_bars.load(Ordering::SeqCst), 1);
+  }
+
+  #[test]
+  fn test_elapsed() {
+    use crate::{
+      clock::ManualClock, music_timer_engine::MusicTimerEngine, time_signature::TimeSignature,
+    };
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(4, 4), 120.0, ManualClock::new());
+    assert_eq!(performer.elapsed(), Duration::default());
+
+    let mut previous_elapsed = performer.elapsed();
+    for millis in 1..20 {
+      performer
+        .get_clock_mut()
+        .advance(Duration::from_millis(millis));
+      performer.pulse(&mut performer_state);
+      assert!(performer.elapsed() > previous_elapsed);
+      previous_elapsed = performer.elapsed();
+    }
+    assert_eq!(performer.elapsed(), Duration::from_millis(19 * 20 / 2));
+  }
+
+  #[test]
+  fn test_double_half_time() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(3, 4, 155.0).unwrap();
+    let interval_duration = performer.get_beat_interval_duration();
+    for _ in 0..10 {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+    let current_time = *performer.get_current_time();
+
+    performer.double_time();
+    assert_eq!(performer.get_bpm(), 310.0);
+    assert!(performer.get_beat_interval_duration() < interval_duration);
+    assert_eq!(performer.get_current_time(), &current_time);
+
+    performer.half_time();
+    assert_eq!(performer.get_bpm(), 155.0);
+    assert_eq!(performer.get_beat_interval_duration(), interval_duration);
+    assert_eq!(performer.get_current_time(), &current_time);
+
+    performer.half_time().double_time();
+    assert_eq!(performer.get_beat_interval_duration(), interval_duration);
+  }
+
+  #[test]
+  fn test_midi_clock() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    performer.start_midi_clock(&mut performer_state);
+    assert!(performer.is_midi_clock_started());
+
+    while performer.get_current_time() < &MusicTime::new(2, 1, 2) {
+      performer.tick(Duration::from_millis(1), &mut performer_state);
+    }
+    performer.stop_midi_clock(&mut performer_state);
+    assert!(!performer.is_midi_clock_started());
+    performer.tick(Duration::from_millis(100), &mut performer_state);
+
+    assert_eq!(
+      performer_state.midi_clocks_on_beat,
+      vec![1, 1 + 24, 1 + 24 * 2, 1 + 24 * 3, 1 + 24 * 4]
+    );
+    assert_eq!(performer_state.midi_transport, vec![true, false]);
+  }
+
+  #[test]
+  fn test_external_clock() {
+    use crate::music_timer_engine::PulseEvent;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    for _ in 0..24 * 4 {
+      performer.on_external_clock(&mut performer_state);
+    }
+    assert_eq!(performer_state.beat_intervals.len(), 8 * 4);
+    assert_eq!(performer_state.beats.len(), 4);
+    assert_eq!(performer.get_current_time(), &MusicTime::new(2, 1, 1));
+
+    // Beats start on the first pulse of each quarter note
+    let events = performer.on_external_clock(&mut performer_state);
+    assert_eq!(
+      events,
+      vec![PulseEvent::BeatInterval, PulseEvent::Beat, PulseEvent::Bar]
+    );
+    assert_eq!(performer_state.bars, vec![MusicTime::new(2, 1, 1)]);
+    for _ in 0..2 {
+      let events = performer.on_external_clock(&mut performer_state);
+      assert_eq!(events, vec![PulseEvent::Nothing]);
+    }
+
+    // A beat of 6/8 is an eighth note
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(6, 8, 120.0).unwrap();
+    for _ in 0..24 * 3 {
+      performer.on_external_clock(&mut performer_state);
+    }
+    assert_eq!(performer_state.beats.len(), 6);
+    assert_eq!(performer.get_current_time(), &MusicTime::new(2, 1, 1));
+  }
+
+  #[test]
+  fn test_start_stop() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    performer.stop();
+    assert!(performer_state.transport.is_empty());
+
+    performer.play();
+    for _ in 0..100 {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+    assert_eq!(performer_state.transport, vec![true]);
+
+    performer.stop();
+    assert_eq!(performer_state.transport, vec![true]);
+    performer.tick(Duration::from_millis(10), &mut performer_state);
+    assert_eq!(performer_state.transport, vec![true, false]);
+
+    let mut performer_state = PerformanceState::new();
+    performer.play();
+    performer.tick(Duration::from_millis(10), &mut performer_state);
+    performer.tick(Duration::from_millis(10), &mut performer_state);
+    assert_eq!(performer_state.transport, vec![true]);
+
+    performer.stop_with(&mut performer_state);
+    assert_eq!(performer_state.transport, vec![true, false]);
+    performer.tick(Duration::from_millis(10), &mut performer_state);
+    assert_eq!(performer_state.transport, vec![true, false]);
+  }
+
+  #[test]
+  fn test_output_latency() {
+    use crate::{
+      clock::ManualClock, music_timer_engine::MusicTimerEngine, time_signature::TimeSignature,
+    };
+    use std::time::Duration;
+
+    let latency = Duration::from_millis(5);
+
+    // The second interval triggers early by the latency
+    let mut performer_state = PerformanceState::new();
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(4, 4), 120.0, ManualClock::new());
+    performer.set_output_latency(latency);
+    let interval_duration = performer.get_beat_interval_duration();
+    performer.pulse(&mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(1, 1, 1)]
+    );
+    performer
+      .get_clock_mut()
+      .set(interval_duration - latency - Duration::from_millis(1));
+    performer.pulse(&mut performer_state);
+    assert_eq!(performer_state.beat_intervals.len(), 1);
+    performer.get_clock_mut().set(interval_duration - latency);
+    performer.pulse(&mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(1, 1, 1), MusicTime::new(1, 1, 2)]
+    );
+
+    // The first interval triggers late by the delay
+    let mut performer_state = PerformanceState::new();
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(4, 4), 120.0, ManualClock::new());
+    performer.set_output_delay(latency);
+    performer.pulse(&mut performer_state);
+    assert!(performer_state.beat_intervals.is_empty());
+    performer.get_clock_mut().set(latency);
+    performer.pulse(&mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(1, 1, 1)]
+    );
+    performer.get_clock_mut().set(interval_duration + latency);
+    performer.pulse(&mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(1, 1, 1), MusicTime::new(1, 1, 2)]
+    );
+
+    // The delay is kept after a stop
+    performer.stop();
+    performer.play();
+    let mut performer_state = PerformanceState::new();
+    performer.pulse(&mut performer_state);
+    assert!(performer_state.beat_intervals.is_empty());
+    assert_eq!(performer.get_output_delay(), latency);
+  }
+
+  #[test]
+  fn test_phase() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    performer.tick(Duration::default(), &mut performer_state);
+    assert_eq!(performer.phase(), 0.0);
+
+    let mut previous_phase = performer.phase();
+    for _ in 0..6 {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+      let phase = performer.phase();
+      assert!((0.0..1.0).contains(&phase));
+      assert!(phase > previous_phase);
+      previous_phase = phase;
+    }
+    assert_eq!(performer_state.beat_intervals.len(), 1);
+
+    // Wraps around on the next beat interval
+    performer.tick(Duration::from_millis(10), &mut performer_state);
+    assert_eq!(performer_state.beat_intervals.len(), 2);
+    assert!(performer.phase() < previous_phase);
+  }
+
+  #[test]
+  fn test_pulse_to_channel() {
+    use crate::{clock::ManualClock, music_timer_engine::*, time_signature::TimeSignature};
+    use std::sync::mpsc;
+
+    let (tx, rx) = mpsc::channel();
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(2, 4), 120.0, ManualClock::new());
+    let interval_duration = performer.get_beat_interval_duration();
+    performer.pulse_to_channel(&tx);
+    for _ in 0..8 * 2 {
+      performer.get_clock_mut().advance(interval_duration);
+      performer.pulse_to_channel(&tx);
+    }
+
+    let received: Vec<(PulseEvent, MusicTime)> = rx.try_iter().collect();
+    assert_eq!(received.len(), 8 * 2 + 1 + 3 + 1);
+    assert_eq!(
+      received[..2],
+      [
+        (PulseEvent::BeatInterval, MusicTime::new(1, 1, 1)),
+        (PulseEvent::Beat, MusicTime::new(1, 1, 1)),
+      ]
+    );
+    assert_eq!(
+      received[received.len() - 3..],
+      [
+        (PulseEvent::BeatInterval, MusicTime::new(2, 1, 1)),
+        (PulseEvent::Beat, MusicTime::new(2, 1, 1)),
+        (PulseEvent::Bar, MusicTime::new(2, 1, 1)),
+      ]
+    );
+
+    // Pulsing carries on without a receiver
+    drop(rx);
+    performer.get_clock_mut().advance(interval_duration);
+    assert_eq!(
+      performer.pulse_to_channel(&tx),
+      vec![PulseEvent::BeatInterval]
+    );
+  }
+
+  #[test]
+  fn test_humanize() {
+    use std::time::Duration;
+
+    let max_jitter = Duration::from_millis(8);
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    performer.set_humanize(max_jitter, 7);
+    let interval_ms = performer.get_beat_interval_duration().as_secs_f64() * 1000.0;
+
+    let mut deviations = Vec::new();
+    let mut elapsed_ms = 0.0;
+    while performer_state.beat_intervals.len() < 400 {
+      let count = performer_state.beat_intervals.len();
+      performer.tick(Duration::from_micros(100), &mut performer_state);
+      if performer_state.beat_intervals.len() > count {
+        deviations.push(elapsed_ms - count as f64 * interval_ms);
+      }
+      elapsed_ms += 0.1;
+    }
+
+    // Each beat interval is within bounds of the grid and the grid doesn't drift
+    assert!(deviations.iter().all(|deviation| deviation.abs() <= 8.1));
+    assert!(deviations.iter().any(|deviation| deviation.abs() > 1.0));
+    let mean = deviations.iter().sum::<f64>() / deviations.len() as f64;
+    assert!(mean.abs() < 1.0, "Mean deviation {}", mean);
+    assert_eq!(
+      performer_state.beat_intervals[399],
+      MusicTime::new(13, 2, 8)
+    );
+  }
+
+  #[test]
+  fn test_builder() {
+    use crate::{
+      music_timer_engine::{BuildError, LoopError, MusicTimerEngineBuilder},
+      time_signature::{TimeSignature, TimeSignatureError},
+    };
+
+    let performer = MusicTimerEngineBuilder::new()
+      .time_signature(TimeSignature::new(6, 8))
+      .bpm(90.0)
+      .swing(0.6)
+      .loop_region(MusicTime::new(1, 1, 1), MusicTime::new(2, 6, 8))
+      .count_in(2)
+      .build()
+      .unwrap();
+    assert_eq!(performer.get_bpm(), 90.0);
+    assert_eq!(performer.get_swing(), 0.6);
+    assert_eq!(
+      performer.get_loop(),
+      Some(&(MusicTime::new(1, 1, 1), MusicTime::new(2, 6, 8)))
+    );
+    assert_eq!(performer.get_current_time(), &MusicTime::new(0, 1, 1));
+
+    let performer = MusicTimerEngineBuilder::new().build().unwrap();
+    assert_eq!(performer.get_bpm(), 120.0);
+    assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 1));
+
+    assert_eq!(
+      MusicTimerEngineBuilder::new()
+        .time_signature(TimeSignature::new(0, 4))
+        .build()
+        .err(),
+      Some(BuildError::TimeSignature(TimeSignatureError::ZeroNumerator))
+    );
+    assert_eq!(
+      MusicTimerEngineBuilder::new()
+        .loop_region(MusicTime::new(2, 1, 1), MusicTime::new(1, 1, 1))
+        .build()
+        .err(),
+      Some(BuildError::Loop(LoopError::TooShort))
+    );
+  }
+
+  #[test]
+  #[should_panic(expected = "The bpm must be finite and greater than zero, got 0")]
+  fn test_zero_bpm() {
+    let _ = crate::create_performance_engine(4, 4, 0.0);
+  }
+
+  #[test]
+  #[should_panic(expected = "The bpm must be finite and greater than zero, got -120")]
+  fn test_negative_bpm() {
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    performer.set_bpm(-120.0);
+  }
+
+  #[test]
+  fn test_builder_invalid_bpm() {
+    use crate::music_timer_engine::{BpmError, BuildError, MusicTimerEngineBuilder};
+
+    for bpm in [0.0, -120.0, f32::NAN, f32::INFINITY] {
+      assert_eq!(
+        MusicTimerEngineBuilder::new().bpm(bpm).build().err(),
+        Some(BuildError::Bpm(BpmError::NotPositive))
+      );
+    }
+  }
+
+  #[test]
+  fn test_builder_invalid_swing() {
+    use crate::music_timer_engine::{BuildError, MusicTimerEngineBuilder, SwingError};
+
+    for ratio in [0.4, 0.8, f32::NAN] {
+      assert_eq!(
+        MusicTimerEngineBuilder::new().swing(ratio).build().err(),
+        Some(BuildError::Swing(SwingError::OutOfRange))
+      );
+    }
+    for ratio in [0.5, 0.75] {
+      let performer = MusicTimerEngineBuilder::new().swing(ratio).build().unwrap();
+      assert_eq!(performer.get_swing(), ratio);
+    }
+  }
+
+  #[test]
+  fn test_time_until() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    assert_eq!(
+      performer.time_until(MusicTime::new(1, 3, 1)),
+      Duration::from_secs(1)
+    );
+
+    // Two beats ahead of the beat interval performed
+    performer.tick(Duration::from_millis(20), &mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(1, 1, 1)]
+    );
+    assert_eq!(
+      performer.time_until(MusicTime::new(1, 3, 1)),
+      Duration::from_millis(980)
+    );
+
+    // The next beat interval is the rest of the current one, performed times have none
+    assert_eq!(
+      performer.time_until(MusicTime::new(1, 1, 2)),
+      Duration::from_millis(42) + Duration::from_micros(500)
+    );
+    assert_eq!(
+      performer.time_until(MusicTime::new(1, 1, 1)),
+      Duration::default()
+    );
+
+    // Far in the future, 65534 bars of 2 seconds
+    assert_eq!(
+      performer.time_until(MusicTime::new(u16::MAX, 1, 1)),
+      Duration::from_secs(131068) - Duration::from_millis(20)
+    );
+  }
+
+  #[test]
+  fn test_set_time_signature() {
+    use crate::time_signature::{TimeSignature, TimeSignatureError};
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    assert_eq!(performer.get_time_signature(), &TimeSignature::new(4, 4));
+    assert_eq!(
+      performer.set_time_signature(TimeSignature::new(3, 5)).err(),
+      Some(TimeSignatureError::UnsupportedDenominator)
+    );
+
+    // The first bar is completed in 4/4
+    while performer.get_current_time() < &MusicTime::new(1, 3, 1) {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+    performer
+      .set_time_signature(TimeSignature::new(3, 4))
+      .unwrap();
+    assert_eq!(performer.get_time_signature(), &TimeSignature::new(4, 4));
+    while performer.get_current_time() <= &MusicTime::new(3, 1, 1) {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+    assert_eq!(performer.get_time_signature(), &TimeSignature::new(3, 4));
+    assert_eq!(
+      performer_state.bars,
+      vec![MusicTime::new(2, 1, 1), MusicTime::new(3, 1, 1)]
+    );
+    assert_eq!(performer_state.beat_intervals.len(), 8 * (4 + 3) + 1);
+  }
+
+  #[test]
+  fn test_bar_progress() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    performer.tick(Duration::default(), &mut performer_state);
+    assert_eq!(performer.bar_progress(), 0.0);
+
+    // Half of a 4/4 bar at 120bpm is a second
+    let mut previous_progress = 0.0;
+    for _ in 0..100 {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+      assert!(performer.bar_progress() > previous_progress);
+      previous_progress = performer.bar_progress();
+    }
+    assert_eq!(
+      performer_state.beat_intervals.last(),
+      Some(&MusicTime::new(1, 3, 1))
+    );
+    assert!((performer.bar_progress() - 0.5).abs() < 0.001);
+  }
+
+  #[test]
+  fn test_recording() {
+    use crate::{
+      clock::ManualClock,
+      music_timer_engine::{MusicTimerEngine, PulseEvent},
+      time_signature::TimeSignature,
+    };
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(2, 4), 120.0, ManualClock::new());
+    performer.pulse(&mut performer_state);
+    assert!(performer.take_recording().is_empty());
+
+    performer.enable_recording();
+    let interval_duration = performer.get_beat_interval_duration();
+    for _ in 0..16 {
+      performer.get_clock_mut().advance(interval_duration);
+      performer.pulse(&mut performer_state);
+    }
+    let recording = performer.take_recording();
+    assert_eq!(recording.len(), 15 + 1 + 3);
+    assert_eq!(
+      recording[0],
+      (
+        Duration::from_micros(62500),
+        PulseEvent::BeatInterval,
+        MusicTime::new(1, 1, 2)
+      )
+    );
+    assert_eq!(
+      recording[16..],
+      [
+        (
+          Duration::from_secs(1),
+          PulseEvent::BeatInterval,
+          MusicTime::new(2, 1, 1)
+        ),
+        (
+          Duration::from_secs(1),
+          PulseEvent::Beat,
+          MusicTime::new(2, 1, 1)
+        ),
+        (
+          Duration::from_secs(1),
+          PulseEvent::Bar,
+          MusicTime::new(2, 1, 1)
+        ),
+      ]
+    );
+    assert!(performer.take_recording().is_empty());
+  }
+
+  #[test]
+  fn test_underrun() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    let interval_duration = performer.get_beat_interval_duration();
+    performer.tick(Duration::default(), &mut performer_state);
+    performer.tick(interval_duration, &mut performer_state);
+    assert!(performer_state.underruns.is_empty());
+
+    // Four beat intervals in one pulse, three of them late
+    performer.tick(interval_duration * 4, &mut performer_state);
+    assert_eq!(performer_state.beat_intervals.len(), 6);
+    assert_eq!(performer_state.underruns, vec![3]);
+  }
+
+  #[test]
+  fn test_nudge() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    let interval_duration = performer.get_beat_interval_duration();
+    performer.tick(Duration::default(), &mut performer_state);
+    let next_time = MusicTime::new(1, 1, 2);
+    assert_eq!(performer.time_until(next_time), interval_duration);
+
+    performer.nudge(Duration::from_millis(10), true);
+    assert_eq!(
+      performer.time_until(next_time),
+      interval_duration - Duration::from_millis(10)
+    );
+    performer.nudge(Duration::from_millis(4), false);
+    assert_eq!(
+      performer.time_until(next_time),
+      interval_duration - Duration::from_millis(6)
+    );
+
+    // Nudges are bound by the beat intervals either side
+    performer.nudge(interval_duration * 2, true);
+    assert_eq!(performer.time_until(next_time), Duration::default());
+    performer.nudge(interval_duration * 2, false);
+    assert_eq!(performer.time_until(next_time), interval_duration);
+
+    performer.nudge(Duration::from_millis(10), true);
+    performer.tick(
+      interval_duration - Duration::from_millis(10),
+      &mut performer_state,
+    );
+    assert_eq!(performer_state.beat_intervals.last(), Some(&next_time));
+  }
+
+  #[test]
+  fn test_beat_duration() {
+    use std::time::Duration;
+
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    assert_eq!(performer.get_beat_duration(), Duration::from_millis(500));
+    assert_eq!(
+      performer.get_beat_duration(),
+      performer.get_beat_interval_duration() * 8
+    );
+
+    performer.set_bpm(150.0);
+    assert_eq!(performer.get_beat_duration(), Duration::from_millis(400));
+    assert_eq!(
+      performer.get_beat_duration(),
+      performer.get_beat_interval_duration() * 8
+    );
+  }
+
+  #[test]
+  fn test_start_on_next_bar() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    performer.set_music_timer(MusicTime::new(1, 3, 1));
+    performer.start_on_next_bar();
+
+    // Two beats of silence before bar 2
+    for _ in 0..99 {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+      assert!(performer_state.transport.is_empty());
+      assert!(performer_state.beat_intervals.is_empty());
+      assert!(performer_state.beats.is_empty());
+    }
+    assert!(performer.is_waiting_for_bar());
+
+    performer.tick(Duration::from_millis(10), &mut performer_state);
+    assert!(!performer.is_waiting_for_bar());
+    assert_eq!(performer_state.transport, vec![true]);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(2, 1, 1)]
+    );
+    assert_eq!(performer_state.beats, vec![MusicTime::new(2, 1, 1)]);
+    assert!(performer_state.bars.is_empty());
+    assert!(performer_state.underruns.is_empty());
+
+    while performer.get_current_time() <= &MusicTime::new(3, 1, 1) {
+      performer.tick(Duration::from_millis(10), &mut performer_state);
+    }
+    assert_eq!(performer_state.bars, vec![MusicTime::new(3, 1, 1)]);
+  }
+
+  #[test]
+  fn test_transport() {
+    use crate::{
+      clock::ManualClock,
+      music_timer_engine::{MusicTimerEngine, PulseEvent, Transport},
+      time_signature::TimeSignature,
+    };
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(4, 4), 120.0, ManualClock::new());
+    let interval_duration = performer.get_beat_interval_duration();
+    assert_eq!(performer.transport(), Transport::Playing);
+    performer.pulse(&mut performer_state);
+    performer.get_clock_mut().advance(interval_duration);
+    performer.pulse(&mut performer_state);
+    assert_eq!(performer_state.beat_intervals.len(), 2);
+
+    // Paused time is not performed
+    performer.pause();
+    assert_eq!(performer.transport(), Transport::Paused);
+    performer.get_clock_mut().advance(Duration::from_secs(10));
+    assert_eq!(
+      performer.pulse(&mut performer_state),
+      vec![PulseEvent::Nothing]
+    );
+    assert_eq!(
+      performer.tick(interval_duration, &mut performer_state),
+      vec![PulseEvent::Nothing]
+    );
+    assert_eq!(performer_state.beat_intervals.len(), 2);
+
+    performer.play();
+    assert_eq!(performer.transport(), Transport::Playing);
+    performer.pulse(&mut performer_state);
+    assert_eq!(performer_state.beat_intervals.len(), 2);
+    performer.get_clock_mut().advance(interval_duration);
+    performer.pulse(&mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals.last(),
+      Some(&MusicTime::new(1, 1, 3))
+    );
+    assert_eq!(performer.elapsed(), interval_duration * 2);
+
+    // A stopped performance can't be paused and plays from the start
+    performer.stop_with(&mut performer_state);
+    assert_eq!(performer.transport(), Transport::Stopped);
+    performer.pause();
+    assert_eq!(performer.transport(), Transport::Stopped);
+    assert_eq!(performer_state.transport, vec![true, false]);
+    let mut performer_state = PerformanceState::new();
+    performer.get_clock_mut().advance(Duration::from_secs(10));
+    performer.pulse(&mut performer_state);
+    assert!(performer_state.beat_intervals.is_empty());
+
+    performer.play();
+    performer.pulse(&mut performer_state);
+    assert_eq!(performer_state.transport, vec![true]);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(1, 1, 1)]
+    );
+    assert_eq!(performer.elapsed(), Duration::default());
+  }
+
+  #[test]
+  fn test_previous_time() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    let interval_duration = performer.get_beat_interval_duration();
+    assert_eq!(performer.previous_time(), &MusicTime::new(0, 0, 0));
+
+    performer.tick(Duration::default(), &mut performer_state);
+    assert_eq!(performer.previous_time(), &MusicTime::new(1, 1, 1));
+    assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 2));
+
+    performer.tick(interval_duration * 8, &mut performer_state);
+    assert_eq!(performer.previous_time(), &MusicTime::new(1, 2, 1));
+    assert_eq!(performer.get_current_time(), &MusicTime::new(1, 2, 2));
+
+    performer.stop();
+    assert_eq!(performer.previous_time(), &MusicTime::new(0, 0, 0));
+  }
+
+  #[test]
+  fn test_sync_to() {
+    use crate::{
+      clock::ManualClock,
+      music_timer_engine::{MusicTimerEngine, PulseEvent},
+      time_signature::TimeSignature,
+    };
+    use std::time::Duration;
+
+    let mut leader_state = PerformanceState::new();
+    let mut leader =
+      MusicTimerEngine::with_clock(TimeSignature::new(4, 4), 120.0, ManualClock::new());
+    for _ in 0..90 {
+      leader.get_clock_mut().advance(Duration::from_millis(1));
+      leader.pulse(&mut leader_state);
+    }
+
+    let mut follower_state = PerformanceState::new();
+    let clock = *leader.get_clock();
+    let mut follower = MusicTimerEngine::with_clock(TimeSignature::new(3, 4), 90.0, clock);
+    follower.sync_to(&leader);
+
+    // Both trigger on the same milliseconds
+    let mut leader_triggers = Vec::new();
+    let mut follower_triggers = Vec::new();
+    for millisecond in 0..2000 {
+      leader.get_clock_mut().advance(Duration::from_millis(1));
+      follower.get_clock_mut().advance(Duration::from_millis(1));
+      if leader.pulse(&mut leader_state)[0] != PulseEvent::Nothing {
+        leader_triggers.push(millisecond);
+      }
+      if follower.pulse(&mut follower_state)[0] != PulseEvent::Nothing {
+        follower_triggers.push(millisecond);
+      }
+    }
+    assert_eq!(leader_triggers.len(), 32);
+    assert_eq!(leader_triggers, follower_triggers);
+    assert_eq!(leader.get_current_time(), &MusicTime::new(2, 1, 3));
+    assert_eq!(follower.get_current_time(), &MusicTime::new(2, 2, 1));
+  }
+
+  #[test]
+  fn test_on_beat_interval_indexed() {
+    use crate::music_timer_engine::MusicTimerState;
+    use std::time::Duration;
+
+    struct IndexedState {
+      indices: Vec<u64>,
+      beat_intervals: usize,
+    }
+    impl MusicTimerState for IndexedState {
+      fn on_beat_interval(&mut self, _current_time: &MusicTime) {
+        self.beat_intervals += 1;
+      }
+      fn on_beat_interval_indexed(&mut self, current_time: &MusicTime, index: u64) {
+        self.indices.push(index);
+        self.on_beat_interval(current_time);
+      }
+      fn on_beat(&mut self, _current_time: &MusicTime) {}
+      fn on_bar(&mut self, _current_time: &MusicTime) {}
+    }
+
+    let mut performer_state = IndexedState {
+      indices: Vec::new(),
+      beat_intervals: 0,
+    };
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    let interval_duration = performer.get_beat_interval_duration();
+    assert_eq!(performer.total_beat_intervals(), 0);
+
+    performer.tick(Duration::default(), &mut performer_state);
+    performer.tick(interval_duration * 40, &mut performer_state);
+    assert_eq!(performer_state.indices, (0..41).collect::<Vec<u64>>());
+    assert_eq!(performer_state.beat_intervals, 41);
+    assert_eq!(performer.total_beat_intervals(), 41);
+
+    // The default forwards to `on_beat_interval`
+    let mut performer_state = PerformanceState::new();
+    performer.stop();
+    assert_eq!(performer.total_beat_intervals(), 0);
+    performer.play();
+    performer.tick(Duration::default(), &mut performer_state);
+    assert_eq!(performer_state.beat_intervals.len(), 1);
+    assert_eq!(performer.total_beat_intervals(), 1);
+  }
+
+  #[test]
+  fn test_set_start_time() {
+    use crate::music_timer_engine::PulseEvent;
+    use std::time::{Duration, Instant, SystemTime};
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    let start = SystemTime::now() + Duration::from_millis(50);
+    performer.set_start_time(start);
+
+    // Nothing before the start
+    let waiting = Instant::now();
+    while waiting.elapsed() < Duration::from_millis(30) {
+      assert_eq!(
+        performer.pulse(&mut performer_state),
+        vec![PulseEvent::Nothing]
+      );
+    }
+    assert!(performer_state.beat_intervals.is_empty());
+
+    // The first beat interval once the start has passed
+    std::thread::sleep(Duration::from_millis(30));
+    assert!(SystemTime::now() > start);
+    performer.pulse(&mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(1, 1, 1)]
+    );
+  }
+
+  #[test]
+  fn test_recalibrate() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    let interval_duration = performer.get_beat_interval_duration();
+    performer.tick(Duration::default(), &mut performer_state);
+    performer.tick(
+      interval_duration - Duration::from_millis(1),
+      &mut performer_state,
+    );
+    assert_eq!(performer_state.beat_intervals.len(), 1);
+
+    // Nearly due, but a seek restarts the timing
+    let mut performer_state = PerformanceState::new();
+    performer.seek(MusicTime::new(2, 1, 1));
+    performer.tick(Duration::from_millis(2), &mut performer_state);
+    assert!(performer_state.beat_intervals.is_empty());
+    performer.tick(interval_duration, &mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(2, 1, 1)]
+    );
+
+    performer.tick(interval_duration / 2, &mut performer_state);
+    performer.recalibrate();
+    performer.tick(
+      interval_duration - Duration::from_millis(1),
+      &mut performer_state,
+    );
+    assert_eq!(performer_state.beat_intervals.len(), 1);
+    performer.tick(Duration::from_millis(1), &mut performer_state);
+    assert_eq!(performer_state.beat_intervals.len(), 2);
+  }
+
+  #[test]
+  fn test_hold() {
+    use crate::{
+      clock::ManualClock,
+      music_timer_engine::{MusicTimerEngine, PulseEvent, Transport},
+      time_signature::TimeSignature,
+    };
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(4, 4), 120.0, ManualClock::new());
+    let interval_duration = performer.get_beat_interval_duration();
+    performer.pulse(&mut performer_state);
+    assert_eq!(performer_state.beat_intervals.len(), 1);
+
+    // Nothing is triggered across several beat intervals while held
+    performer.hold();
+    assert_eq!(performer.transport(), Transport::Playing);
+    for _ in 0..5 {
+      performer.get_clock_mut().advance(interval_duration);
+      assert_eq!(
+        performer.pulse(&mut performer_state),
+        vec![PulseEvent::Nothing]
+      );
+    }
+    assert_eq!(performer_state.beat_intervals.len(), 1);
+    assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 2));
+    assert_eq!(performer.elapsed(), interval_duration * 5);
+
+    // The next beat interval is a full beat interval after the release
+    performer.release();
+    performer.pulse(&mut performer_state);
+    assert_eq!(performer_state.beat_intervals.len(), 1);
+    performer.get_clock_mut().advance(interval_duration);
+    performer.pulse(&mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(1, 1, 1), MusicTime::new(1, 1, 2)]
+    );
+
+    performer.hold();
+    performer.stop();
+    assert!(!performer.is_held());
+  }
+
+  #[test]
+  fn test_control_flow() {
+    use crate::music_timer_engine::{MusicTimerState, Transport};
+    use std::{ops::ControlFlow, time::Duration};
+
+    struct EndingState {
+      beat_intervals: Vec<MusicTime>,
+      bars: Vec<MusicTime>,
+      stops: usize,
+      is_ended: bool,
+    }
+    impl MusicTimerState for EndingState {
+      fn on_beat_interval(&mut self, current_time: &MusicTime) {
+        self.beat_intervals.push(*current_time);
+      }
+      fn on_beat(&mut self, _current_time: &MusicTime) {}
+      fn on_bar(&mut self, current_time: &MusicTime) {
+        self.bars.push(*current_time);
+        self.is_ended = current_time.get_bar() == 3;
+      }
+      fn on_stop(&mut self) {
+        self.stops += 1;
+      }
+      fn control_flow(&self) -> ControlFlow<()> {
+        if self.is_ended {
+          ControlFlow::Break(())
+        } else {
+          ControlFlow::Continue(())
+        }
+      }
+    }
+
+    let mut performer_state = EndingState {
+      beat_intervals: Vec::new(),
+      bars: Vec::new(),
+      stops: 0,
+      is_ended: false,
+    };
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    let interval_duration = performer.get_beat_interval_duration();
+
+    // Catch up well past the end of bar 2 in a single pulse
+    performer.tick(Duration::default(), &mut performer_state);
+    performer.tick(interval_duration * 80, &mut performer_state);
+    assert_eq!(performer.transport(), Transport::Stopped);
+    assert_eq!(performer_state.stops, 1);
+    assert_eq!(
+      performer_state.bars,
+      vec![MusicTime::new(2, 1, 1), MusicTime::new(3, 1, 1)]
+    );
+    assert_eq!(performer_state.beat_intervals.len(), 65);
+    assert_eq!(
+      performer_state.beat_intervals.last(),
+      Some(&MusicTime::new(3, 1, 1))
+    );
+
+    // No later callbacks
+    performer.tick(interval_duration * 8, &mut performer_state);
+    assert_eq!(performer_state.beat_intervals.len(), 65);
+    assert_eq!(performer_state.bars.len(), 2);
+  }
+
+  #[test]
+  fn test_free_running() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    let beat_duration = performer.get_beat_duration();
+    performer.set_free_running(true);
+
+    performer.tick(Duration::default(), &mut performer_state);
+    for _ in 0..100 {
+      performer.tick(beat_duration, &mut performer_state);
+      assert_eq!(performer.get_current_time().get_bar(), 1);
+    }
+    assert_eq!(performer.previous_time(), &MusicTime::new(1, 101, 1));
+    assert_eq!(performer_state.beats.len(), 101);
+    assert!(performer_state.bars.is_empty());
+    assert_eq!(performer.total_beat_intervals(), 801);
+  }
+
+  #[test]
+  fn test_effective_interval_duration() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    let start_duration = performer.get_beat_interval_duration();
+    assert_eq!(performer.effective_interval_duration(), start_duration);
+
+    performer.ramp_bpm(180.0, MusicTime::new(3, 1, 1));
+    performer.tick(Duration::default(), &mut performer_state);
+    while performer.get_current_time() < &MusicTime::new(2, 1, 1) {
+      performer.tick(Duration::from_millis(1), &mut performer_state);
+    }
+
+    // Midway through the ramp
+    let target_duration = Duration::from_secs(60) / 180 / 8;
+    let effective_duration = performer.effective_interval_duration();
+    assert!(effective_duration < start_duration);
+    assert!(effective_duration > target_duration);
+
+    // Smoothly between the beat interval durations around it
+    let interval_duration = performer.get_beat_interval_duration();
+    let mut previous_duration = effective_duration;
+    while performer.get_current_time() < &MusicTime::new(2, 1, 3) {
+      performer.tick(Duration::from_millis(1), &mut performer_state);
+      let effective_duration = performer.effective_interval_duration();
+      assert!(effective_duration <= previous_duration);
+      previous_duration = effective_duration;
+    }
+    assert!(previous_duration < interval_duration);
+
+    // The ramp is complete
+    while performer.get_current_time() < &MusicTime::new(3, 2, 1) {
+      performer.tick(Duration::from_millis(1), &mut performer_state);
+    }
+    assert_eq!(
+      performer.effective_interval_duration(),
+      performer.get_beat_interval_duration()
+    );
+  }
+
+  #[test]
+  fn test_phrase() {
+    use crate::music_timer_engine::MusicTimerState;
+    use std::time::Duration;
+
+    struct PhraseState {
+      bars: Vec<MusicTime>,
+      phrases: Vec<MusicTime>,
+    }
+    impl MusicTimerState for PhraseState {
+      fn on_beat_interval(&mut self, _current_time: &MusicTime) {}
+      fn on_beat(&mut self, _current_time: &MusicTime) {}
+      fn on_bar(&mut self, current_time: &MusicTime) {
+        self.bars.push(*current_time);
+      }
+      fn on_phrase(&mut self, current_time: &MusicTime) {
+        assert_eq!(self.bars.last(), Some(current_time));
+        self.phrases.push(*current_time);
+      }
+    }
+
+    let mut performer_state = PhraseState {
+      bars: Vec::new(),
+      phrases: Vec::new(),
+    };
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    performer.set_phrase_length(4);
+
+    // Over 9 bars
+    let bar_duration = performer.get_beat_duration() * 4;
+    performer.tick(Duration::default(), &mut performer_state);
+    for _ in 0..8 {
+      performer.tick(bar_duration, &mut performer_state);
+    }
+    assert_eq!(performer.previous_time(), &MusicTime::new(9, 1, 1));
+    assert_eq!(performer_state.bars.len(), 8);
+    assert_eq!(
+      performer_state.phrases,
+      vec![MusicTime::new(5, 1, 1), MusicTime::new(9, 1, 1)]
+    );
+
+    // Phrases are off by default
+    let mut performer_state = PhraseState {
+      bars: Vec::new(),
+      phrases: Vec::new(),
+    };
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    performer.tick(Duration::default(), &mut performer_state);
+    performer.tick(bar_duration * 8, &mut performer_state);
+    assert_eq!(performer_state.bars.len(), 8);
+    assert!(performer_state.phrases.is_empty());
+  }
+
+  #[test]
+  fn test_debug_state() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(3, 4, 155.0).unwrap();
+    performer.tick(Duration::default(), &mut performer_state);
+    let summary = performer.debug_state();
+    assert!(summary.contains("155bpm"));
+    assert!(summary.contains("1.1.2"));
+    assert!(summary.contains("3/4"));
+    assert!(summary.starts_with("Playing"));
+
+    let debug = format!("{:?}", performer);
+    assert!(debug.starts_with("MusicTimerEngine"));
+    assert!(debug.contains("bpm: 155.0"));
+  }
+
+  #[test]
+  fn test_groove() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    let late = Duration::from_millis(10);
+    performer.set_groove(vec![Duration::default(), late]);
+    assert_eq!(performer.get_groove(), &[Duration::default(), late]);
+
+    // Record the microsecond each beat interval is triggered on
+    let mut trigger_times = Vec::new();
+    for microsecond in 0..1000001 {
+      let count = performer_state.beat_intervals.len();
+      performer.tick(Duration::from_micros(1), &mut performer_state);
+      if performer_state.beat_intervals.len() > count {
+        trigger_times.push(microsecond);
+      }
+    }
+
+    // Every second beat interval is 10ms late of its 62.5ms grid, the others are on it
+    for (beat_interval, trigger_time) in trigger_times.iter().take(16).enumerate() {
+      let grid_time = beat_interval as i32 * 62500;
+      let expected = if beat_interval % 2 == 1 {
+        grid_time + 10000
+      } else {
+        grid_time
+      };
+      assert!((trigger_time - expected).abs() <= 2);
+    }
+    assert_eq!(performer_state.beat_intervals[1], MusicTime::new(1, 1, 2));
+    assert_eq!(performer_state.beat_intervals[8], MusicTime::new(1, 2, 1));
+  }
+
+  #[test]
+  fn test_has_passed() {
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    let interval_duration = performer.get_beat_interval_duration();
+    let target = MusicTime::new(1, 2, 1);
+
+    performer.tick(Duration::default(), &mut performer_state);
+    performer.tick(interval_duration * 6, &mut performer_state);
+    assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 8));
+    assert!(!performer.has_passed(&target));
+
+    performer.tick(interval_duration, &mut performer_state);
+    assert_eq!(performer.get_current_time(), &target);
+    assert!(!performer.has_passed(&target));
+
+    // Passed once triggered
+    performer.tick(interval_duration, &mut performer_state);
+    assert_eq!(performer_state.beat_intervals.last(), Some(&target));
+    assert!(performer.has_passed(&target));
+  }
+
+  #[test]
+  fn test_at() {
+    use crate::{
+      clock::ManualClock, music_timer_engine::MusicTimerEngine, time_signature::TimeSignature,
+    };
+    use std::sync::{Arc, Mutex};
+
+    let fired = Arc::new(Mutex::new(Vec::new()));
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(4, 4), 120.0, ManualClock::new());
+    let interval_duration = performer.get_beat_interval_duration();
+    let chorus = fired.clone();
+    let verse = fired.clone();
+    performer
+      .at(MusicTime::new(2, 1, 1), move || {
+        chorus.lock().unwrap().push("Chorus")
+      })
+      .at(MusicTime::new(1, 3, 1), move || {
+        verse.lock().unwrap().push("Verse")
+      });
+
+    performer.pulse_closures();
+    for _ in 0..8 * 4 * 2 {
+      performer.get_clock_mut().advance(interval_duration);
+      performer.pulse_closures();
+    }
+    assert_eq!(*fired.lock().unwrap(), vec!["Verse", "Chorus"]);
+
+    // Removed once fired, playing over the same music times again does not fire them
+    performer.seek(MusicTime::new(1, 1, 1));
+    for _ in 0..8 * 4 * 2 {
+      performer.get_clock_mut().advance(interval_duration);
+      performer.pulse_closures();
+    }
+    assert_eq!(*fired.lock().unwrap(), vec!["Verse", "Chorus"]);
+  }
+
+  #[test]
+  fn test_tempo_map() {
+    use crate::{tempo_map::TempoMap, time_signature::TimeSignature};
+    use std::time::Duration;
+
+    let mut tempo_map = TempoMap::new();
+    tempo_map
+      .add_bpm(MusicTime::new(1, 1, 1), 120.0)
+      .unwrap()
+      .add_bpm(MusicTime::new(2, 1, 1), 60.0)
+      .unwrap();
+    tempo_map
+      .add_time_signature(MusicTime::new(2, 1, 1), TimeSignature::new(3, 4))
+      .unwrap();
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 90.0).unwrap();
+    performer.set_tempo_map(tempo_map);
+    assert_eq!(performer.get_bpm(), 120.0);
+    let interval_duration = performer.get_beat_interval_duration();
+
+    // The last beat interval of bar 1 is still at 120bpm
+    performer.tick(Duration::default(), &mut performer_state);
+    performer.tick(interval_duration * 31, &mut performer_state);
+    assert_eq!(performer.get_current_time(), &MusicTime::new(2, 1, 1));
+    assert_eq!(performer.get_beat_interval_duration(), interval_duration);
+    assert_eq!(performer.get_time_signature(), &TimeSignature::new(3, 4));
+
+    // Bar 2 is at 60bpm
+    performer.tick(interval_duration, &mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals.last(),
+      Some(&MusicTime::new(2, 1, 1))
+    );
+    assert_eq!(performer.get_bpm(), 60.0);
+    assert_eq!(
+      performer.get_beat_interval_duration(),
+      interval_duration * 2
+    );
+    performer.tick(interval_duration, &mut performer_state);
+    assert_eq!(performer.get_current_time(), &MusicTime::new(2, 1, 2));
+    performer.tick(interval_duration, &mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals.last(),
+      Some(&MusicTime::new(2, 1, 2))
+    );
+
+    // Bar 2 is in 3/4
+    performer.tick(interval_duration * 2 * 22, &mut performer_state);
+    assert_eq!(performer.get_current_time(), &MusicTime::new(3, 1, 1));
+
+    // Stopping follows the tempo map back to the start
+    performer.stop();
+    assert_eq!(performer.get_bpm(), 120.0);
+    assert_eq!(performer.get_time_signature(), &TimeSignature::new(3, 4));
+  }
+
+  #[test]
+  fn test_start_delay() {
+    use crate::{
+      clock::ManualClock, music_timer_engine::MusicTimerEngine, time_signature::TimeSignature,
+    };
+    use std::time::Duration;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(4, 4), 120.0, ManualClock::new())
+        .with_start_delay(Duration::from_secs(2));
+    let interval_duration = performer.get_beat_interval_duration();
+
+    // Nothing during the delay
+    performer.pulse(&mut performer_state);
+    for _ in 0..199 {
+      performer.get_clock_mut().advance(Duration::from_millis(10));
+      performer.pulse(&mut performer_state);
+    }
+    assert!(performer_state.beat_intervals.is_empty());
+    assert!(performer_state.beats.is_empty());
+    assert!(performer_state.bars.is_empty());
+
+    // Starts at the start of the performance once elapsed
+    performer.get_clock_mut().advance(Duration::from_millis(10));
+    performer.pulse(&mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(1, 1, 1)]
+    );
+    performer.get_clock_mut().advance(interval_duration);
+    performer.pulse(&mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(1, 1, 1), MusicTime::new(1, 1, 2)]
+    );
+  }
+
+  #[test]
+  fn test_verify_timing() {
+    use crate::{
+      clock::ManualClock, music_timer_engine::MusicTimerEngine, time_signature::TimeSignature,
+    };
+    use std::time::Duration;
+
+    let uneven_ticks = [3, 17, 1, 9, 25, 4, 11]
+      .iter()
+      .map(|millis| Duration::from_millis(*millis))
+      .cycle()
+      .take(700)
+      .collect::<Vec<Duration>>();
+    let total: Duration = uneven_ticks.iter().sum();
+
+    let mut performer =
+      MusicTimerEngine::with_clock(TimeSignature::new(3, 4), 155.0, ManualClock::new());
+    let error = performer.verify_timing(&uneven_ticks);
+    assert!(error < Duration::from_millis(25), "Drifted by {:?}", error);
+
+    // All the beat intervals that were due have been performed
+    let beat_intervals =
+      (total.as_nanos() / performer.get_beat_interval_duration().as_nanos()) as i64;
+    let time_signature = TimeSignature::new(3, 4);
+    assert_eq!(
+      performer
+        .get_current_time()
+        .to_beat_interval_index(&time_signature),
+      MusicTime::new(1, 1, 1).to_beat_interval_index(&time_signature) + beat_intervals + 1
+    );
+  }
+
+  #[test]
+  #[allow(deprecated)]
+  fn test_time_signature() {
+    use crate::time_signature::TimeSignature;
+
+    let performer = crate::create_performance_engine(3, 4, 120.0).unwrap();
+    assert_eq!(performer.get_time_signature(), &TimeSignature::new(3, 4));
+    assert_eq!(performer.time_signature(), performer.get_time_signature());
+  }
+
+  #[test]
+  fn test_seek_external_clock() {
+    use crate::music_timer_engine::PulseEvent;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    for _ in 0..10 {
+      performer.on_external_clock(&mut performer_state);
+    }
+
+    let mut performer_state = PerformanceState::new();
+    performer.seek(MusicTime::new(3, 1, 1));
+    let events = performer.on_external_clock(&mut performer_state);
+    assert_eq!(events, vec![PulseEvent::BeatInterval, PulseEvent::Beat]);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(3, 1, 1)]
+    );
+    assert_eq!(performer_state.beats, vec![MusicTime::new(3, 1, 1)]);
+
+    // The next beat interval follows on the same grid as from the start
+    for _ in 0..2 {
+      let events = performer.on_external_clock(&mut performer_state);
+      assert_eq!(events, vec![PulseEvent::Nothing]);
+    }
+    performer.on_external_clock(&mut performer_state);
+    assert_eq!(
+      performer_state.beat_intervals,
+      vec![MusicTime::new(3, 1, 1), MusicTime::new(3, 1, 2)]
+    );
+  }
+
+  #[test]
+  fn test_phase_zero_target() {
+    use std::time::Duration;
+
+    let mut performer = crate::create_performance_engine(4, 4, 120.0).unwrap();
+    let mut groove = vec![Duration::default(); 8];
+    groove[7] = Duration::from_secs(1);
+    performer.set_groove(groove);
+    assert!(performer.event_trigger_target.is_zero());
+    assert_eq!(performer.phase(), 0.0);
   }
 }
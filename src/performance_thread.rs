@@ -0,0 +1,210 @@
+#![allow(dead_code)]
+
+//!
+//! Runner of the performance engine on its own thread.
+//!
+
+use super::{
+    clock::Clock,
+    music_timer_engine::{MusicTimerEngine, MusicTimerState, Transport},
+};
+use std::{
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+const COMMAND_PLAY: u8 = 0;
+const COMMAND_PAUSE: u8 = 1;
+const COMMAND_STOP: u8 = 2;
+
+/// Handle to control a performance running on its own thread, see `spawn`. Dropping the
+/// handle detaches the thread and the performance carries on.
+pub struct PerformanceHandle<C: Clock, TimerState: MusicTimerState> {
+    command: Arc<AtomicU8>,
+    thread: JoinHandle<(MusicTimerEngine<C>, TimerState)>,
+}
+
+impl<C: Clock, TimerState: MusicTimerState> PerformanceHandle<C, TimerState> {
+    /// Pause the performance, see `MusicTimerEngine::pause`.
+    pub fn pause(&self) {
+        self.send(COMMAND_PAUSE);
+    }
+
+    /// Resume the paused performance, see `MusicTimerEngine::play`.
+    pub fn resume(&self) {
+        self.send(COMMAND_PLAY);
+    }
+
+    /// Stop the performance and end its thread, see `MusicTimerEngine::stop`.
+    pub fn stop(&self) {
+        self.send(COMMAND_STOP);
+    }
+
+    /// Wait for the thread of the performance to end after `stop`. The engine and state
+    /// are given back, or the panic if a callback panicked.
+    pub fn join(self) -> thread::Result<(MusicTimerEngine<C>, TimerState)> {
+        self.thread.join()
+    }
+
+    fn send(&self, command: u8) {
+        self.command.store(command, Ordering::SeqCst);
+        self.thread.thread().unpark();
+    }
+}
+
+/// Run the performance on its own thread until stopped with the returned handle. The
+/// engine is pulsed every half of the beat interval duration, sleeping in between.
+///
+/// # Arguments
+/// * `engine` - The engine to pulse.
+/// * `state` - The _trait_ `MusicTimerState` used for changes in music time callbacks.
+///
+/// # Example
+/// ```
+/// use music_timer::{
+///     music_time::MusicTime, music_timer_engine::MusicTimerState, performance_thread,
+/// };
+/// struct PerformanceState;
+/// impl MusicTimerState for PerformanceState {
+///     fn on_beat_interval(&mut self, current_time: &MusicTime) {}
+///     fn on_beat(&mut self, current_time: &MusicTime) {}
+///     fn on_bar(&mut self, current_time: &MusicTime) {}
+/// }
+/// let performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
+/// let handle = performance_thread::spawn(performer, PerformanceState {});
+/// handle.stop();
+/// let (performer, performer_state) = handle.join().unwrap();
+/// ```
+pub fn spawn<C, TimerState>(
+    mut engine: MusicTimerEngine<C>,
+    mut state: TimerState,
+) -> PerformanceHandle<C, TimerState>
+where
+    C: Clock + Send + 'static,
+    TimerState: MusicTimerState + Send + 'static,
+{
+    let command = Arc::new(AtomicU8::new(COMMAND_PLAY));
+    let thread_command = command.clone();
+    let thread = thread::spawn(move || {
+        engine.play();
+        loop {
+            match thread_command.load(Ordering::SeqCst) {
+                COMMAND_STOP => break,
+                COMMAND_PAUSE => {
+                    engine.pause();
+                    thread::park();
+                    continue;
+                }
+                _ => match engine.transport() {
+                    Transport::Stopped => break,
+                    Transport::Paused => {
+                        engine.play();
+                    }
+                    Transport::Playing => {}
+                },
+            }
+
+            engine.pulse(&mut state);
+            thread::park_timeout(engine.get_beat_interval_duration() / 2);
+        }
+        engine.stop_with(&mut state);
+        (engine, state)
+    });
+
+    PerformanceHandle { command, thread }
+}
+
+mod tests {
+    #[test]
+    fn test_spawn() {
+        use crate::{
+            music_time::MusicTime,
+            music_timer_engine::{MusicTimerState, Transport},
+            performance_thread,
+        };
+        use std::{thread, time::Duration};
+
+        struct PerformanceState {
+            beat_intervals: Vec<MusicTime>,
+            is_stopped: bool,
+        }
+        impl MusicTimerState for PerformanceState {
+            fn on_beat_interval(&mut self, current_time: &MusicTime) {
+                self.beat_intervals.push(*current_time);
+            }
+            fn on_beat(&mut self, _current_time: &MusicTime) {}
+            fn on_bar(&mut self, _current_time: &MusicTime) {}
+            fn on_stop(&mut self) {
+                self.is_stopped = true;
+            }
+        }
+
+        let performer_state = PerformanceState {
+            beat_intervals: Vec::new(),
+            is_stopped: false,
+        };
+        let performer = crate::create_performance_engine(4, 4, 240.0).unwrap();
+        let handle = performance_thread::spawn(performer, performer_state);
+        thread::sleep(Duration::from_millis(100));
+        handle.pause();
+        thread::sleep(Duration::from_millis(50));
+        handle.resume();
+        thread::sleep(Duration::from_millis(100));
+        handle.stop();
+
+        let (performer, performer_state) = handle.join().unwrap();
+        assert_eq!(performer.transport(), Transport::Stopped);
+        assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 1));
+        assert!(performer_state.is_stopped);
+        assert!(performer_state.beat_intervals.len() > 1);
+        assert_eq!(performer_state.beat_intervals[0], MusicTime::new(1, 1, 1));
+        assert_eq!(performer_state.beat_intervals[1], MusicTime::new(1, 1, 2));
+    }
+
+    #[test]
+    fn test_spawn_break() {
+        use crate::{
+            music_time::MusicTime,
+            music_timer_engine::{MusicTimerState, Transport},
+            performance_thread,
+        };
+        use std::{ops::ControlFlow, thread, time::Duration};
+
+        struct PerformanceState {
+            bars: Vec<MusicTime>,
+            is_break: bool,
+        }
+        impl MusicTimerState for PerformanceState {
+            fn on_beat_interval(&mut self, _current_time: &MusicTime) {}
+            fn on_beat(&mut self, _current_time: &MusicTime) {}
+            fn on_bar(&mut self, current_time: &MusicTime) {
+                self.bars.push(*current_time);
+                self.is_break = current_time.get_bar() == 2;
+            }
+            fn control_flow(&self) -> ControlFlow<()> {
+                if self.is_break {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            }
+        }
+
+        let performer_state = PerformanceState {
+            bars: Vec::new(),
+            is_break: false,
+        };
+        let performer = crate::create_performance_engine(4, 4, 480.0).unwrap();
+        let handle = performance_thread::spawn(performer, performer_state);
+        thread::sleep(Duration::from_millis(1500));
+        handle.stop();
+
+        let (performer, performer_state) = handle.join().unwrap();
+        assert_eq!(performer.transport(), Transport::Stopped);
+        assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 1));
+        assert_eq!(performer_state.bars, vec![MusicTime::new(2, 1, 1)]);
+    }
+}
@@ -0,0 +1,125 @@
+#![allow(dead_code)]
+
+//!
+//! Async runner of the performance engine, enabled with the `tokio` feature.
+//!
+
+use super::{
+    clock::Clock,
+    music_time::MusicTime,
+    music_timer_engine::{MusicTimerEngine, MusicTimerState, Transport},
+};
+
+/// Run the performance until `until` has been performed, pulsing the engine on a
+/// `tokio` interval of half the beat interval duration instead of sleeping the thread.
+/// Returns early if the performance is stopped, such as by `MusicTimerState::control_flow`.
+///
+/// # Arguments
+/// * `engine` - The engine to pulse.
+/// * `state` - The _trait_ `MusicTimerState` used for changes in music time callbacks.
+/// * `until` - The last music time to perform.
+///
+/// # Example
+/// ```
+/// use music_timer::{music_time::MusicTime, music_timer_engine::MusicTimerState, runner};
+/// struct PerformanceState;
+/// impl MusicTimerState for PerformanceState {
+///     fn on_beat_interval(&mut self, current_time: &MusicTime) {}
+///     fn on_beat(&mut self, current_time: &MusicTime) {}
+///     fn on_bar(&mut self, current_time: &MusicTime) {}
+/// }
+/// async fn perform() {
+///     let mut performer_state = PerformanceState {};
+///     let mut performer = music_timer::create_performance_engine(3, 4, 155.0).unwrap();
+///     runner::run(&mut performer, &mut performer_state, MusicTime::new(4, 3, 8)).await;
+/// }
+/// ```
+pub async fn run<C: Clock, TimerState: MusicTimerState>(
+    engine: &mut MusicTimerEngine<C>,
+    state: &mut TimerState,
+    until: MusicTime,
+) {
+    let mut interval = tokio::time::interval(engine.get_beat_interval_duration() / 2);
+    while engine.get_current_time() <= &until {
+        interval.tick().await;
+        engine.pulse(state);
+        if engine.transport() == Transport::Stopped {
+            break;
+        }
+    }
+}
+
+mod tests {
+    #[tokio::test]
+    async fn test_run() {
+        use crate::{music_time::MusicTime, music_timer_engine::MusicTimerState, runner};
+
+        struct PerformanceState {
+            beat_intervals: Vec<MusicTime>,
+        }
+        impl MusicTimerState for PerformanceState {
+            fn on_beat_interval(&mut self, current_time: &MusicTime) {
+                self.beat_intervals.push(*current_time);
+            }
+            fn on_beat(&mut self, _current_time: &MusicTime) {}
+            fn on_bar(&mut self, _current_time: &MusicTime) {}
+        }
+
+        let mut performer_state = PerformanceState {
+            beat_intervals: Vec::new(),
+        };
+        let mut performer = crate::create_performance_engine(4, 4, 240.0).unwrap();
+        let end_time = MusicTime::new(1, 2, 1);
+        runner::run(&mut performer, &mut performer_state, end_time).await;
+
+        assert_eq!(performer_state.beat_intervals.len(), 9);
+        assert_eq!(performer_state.beat_intervals.last(), Some(&end_time));
+        assert_eq!(performer.get_current_time(), &MusicTime::new(1, 2, 2));
+    }
+
+    #[tokio::test]
+    async fn test_run_break() {
+        use crate::{
+            music_time::MusicTime,
+            music_timer_engine::{MusicTimerState, Transport},
+            runner,
+        };
+        use std::ops::ControlFlow;
+
+        struct PerformanceState {
+            bars: Vec<MusicTime>,
+            is_break: bool,
+        }
+        impl MusicTimerState for PerformanceState {
+            fn on_beat_interval(&mut self, _current_time: &MusicTime) {}
+            fn on_beat(&mut self, _current_time: &MusicTime) {}
+            fn on_bar(&mut self, current_time: &MusicTime) {
+                self.bars.push(*current_time);
+                self.is_break = current_time.get_bar() == 2;
+            }
+            fn control_flow(&self) -> ControlFlow<()> {
+                if self.is_break {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            }
+        }
+
+        let mut performer_state = PerformanceState {
+            bars: Vec::new(),
+            is_break: false,
+        };
+        let mut performer = crate::create_performance_engine(4, 4, 480.0).unwrap();
+        runner::run(
+            &mut performer,
+            &mut performer_state,
+            MusicTime::new(8, 1, 1),
+        )
+        .await;
+
+        assert_eq!(performer.transport(), Transport::Stopped);
+        assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 1));
+        assert_eq!(performer_state.bars, vec![MusicTime::new(2, 1, 1)]);
+    }
+}
@@ -0,0 +1,240 @@
+#![allow(dead_code)]
+
+//!
+//! Data structure that holds events to be triggered in music time.
+//!
+
+use super::{music_time::MusicTime, time_signature::TimeSignature};
+
+#[derive(Clone, Debug)]
+/// Data structure that holds events sorted by music time, to be drained as the
+/// performance reaches them.
+pub struct Scheduler<T> {
+    events: Vec<(MusicTime, T)>,
+}
+
+impl<T> Scheduler<T> {
+    /// Create a new empty `Scheduler`.
+    ///
+    /// # Example
+    /// ```
+    /// let scheduler = music_timer::scheduler::Scheduler::<&str>::new();
+    /// ```
+    pub fn new() -> Self {
+        Scheduler { events: Vec::new() }
+    }
+
+    /// Schedule an event. Events at the same time are kept in the order they were scheduled.
+    ///
+    /// # Arguments
+    /// * `time` - The music time the event is due.
+    /// * `payload` - The event.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{music_time::MusicTime, scheduler::Scheduler};
+    /// let mut scheduler = Scheduler::new();
+    /// scheduler.schedule(MusicTime::new(2, 1, 1), "Kick");
+    /// scheduler.schedule(MusicTime::new(1, 3, 1), "Snare");
+    /// assert_eq!(scheduler.advance(&MusicTime::new(1, 3, 1)), vec!["Snare"]);
+    /// ```
+    pub fn schedule(&mut self, time: MusicTime, payload: T) -> &mut Self {
+        let index = self
+            .events
+            .partition_point(|(event_time, _)| event_time <= &time);
+        self.events.insert(index, (time, payload));
+        self
+    }
+
+    /// Drain all the events due at or before `now`, in order.
+    ///
+    /// # Arguments
+    /// * `now` - The current music time, _e.g._ from `MusicTimerState::on_beat_interval`.
+    pub fn advance(&mut self, now: &MusicTime) -> Vec<T> {
+        let count = self
+            .events
+            .partition_point(|(event_time, _)| event_time <= now);
+        self.events
+            .drain(..count)
+            .map(|(_, payload)| payload)
+            .collect()
+    }
+
+    /// Drain all the events due within `tolerance_intervals` beat intervals of `now`,
+    /// early or late, in order. Events more than `tolerance_intervals` late are dropped
+    /// without being returned. Handy when `now` is not every beat interval, _e.g._ the
+    /// music times of `MusicTimerState::on_beat`.
+    ///
+    /// # Arguments
+    /// * `now` - The current music time.
+    /// * `time_signature` - The time signature to count the beat intervals within.
+    /// * `tolerance_intervals` - The number of beat intervals an event may be early or late.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{music_time::MusicTime, scheduler::Scheduler, time_signature::TimeSignature};
+    /// let time_signature = TimeSignature::new(4, 4);
+    /// let mut scheduler = Scheduler::new();
+    /// scheduler.schedule(MusicTime::new(1, 1, 1), "Kick");
+    /// scheduler.schedule(MusicTime::new(1, 2, 2), "Snare");
+    /// // The kick is too late and the snare is early enough
+    /// let events = scheduler.advance_within(&MusicTime::new(1, 2, 1), &time_signature, 1);
+    /// assert_eq!(events, vec!["Snare"]);
+    /// assert!(scheduler.is_empty());
+    /// ```
+    pub fn advance_within(
+        &mut self,
+        now: &MusicTime,
+        time_signature: &TimeSignature,
+        tolerance_intervals: u64,
+    ) -> Vec<T> {
+        let latest = now.offset_by(tolerance_intervals, time_signature);
+        let tolerance = tolerance_intervals.min(i64::MAX as u64) as i64;
+        let earliest = now
+            .to_beat_interval_index(time_signature)
+            .saturating_sub(tolerance);
+        let count = self
+            .events
+            .partition_point(|(event_time, _)| event_time <= &latest);
+        self.events
+            .drain(..count)
+            .filter(|(event_time, _)| event_time.to_beat_interval_index(time_signature) >= earliest)
+            .map(|(_, payload)| payload)
+            .collect()
+    }
+
+    /// Gets the music time of the next event, if any.
+    pub fn next_time(&self) -> Option<&MusicTime> {
+        self.events.first().map(|(time, _)| time)
+    }
+
+    /// Gets the number of events yet to be drained.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns `true` if there are no events yet to be drained.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl<T> Default for Scheduler<T> {
+    /// Default `Scheduler` is empty.
+    fn default() -> Self {
+        Scheduler::new()
+    }
+}
+
+mod tests {
+    #[test]
+    fn test_schedule_order() {
+        use crate::{music_time::MusicTime, scheduler::Scheduler};
+
+        let mut scheduler = Scheduler::new();
+        scheduler
+            .schedule(MusicTime::new(2, 1, 1), 'b')
+            .schedule(MusicTime::new(1, 1, 1), 'a')
+            .schedule(MusicTime::new(2, 1, 1), 'c');
+        assert_eq!(scheduler.len(), 3);
+        assert_eq!(scheduler.next_time(), Some(&MusicTime::new(1, 1, 1)));
+        assert_eq!(
+            scheduler.advance(&MusicTime::new(4, 1, 1)),
+            vec!['a', 'b', 'c']
+        );
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn test_advance_with_engine() {
+        use crate::{
+            music_time::MusicTime, music_timer_engine::MusicTimerState, scheduler::Scheduler,
+        };
+        use std::time::Duration;
+
+        struct PerformanceState {
+            scheduler: Scheduler<&'static str>,
+            triggered: Vec<(MusicTime, &'static str)>,
+        }
+        impl MusicTimerState for PerformanceState {
+            fn on_beat_interval(&mut self, current_time: &MusicTime) {
+                for event in self.scheduler.advance(current_time) {
+                    self.triggered.push((*current_time, event));
+                }
+            }
+            fn on_beat(&mut self, _current_time: &MusicTime) {}
+            fn on_bar(&mut self, _current_time: &MusicTime) {}
+        }
+
+        let mut performer_state = PerformanceState {
+            scheduler: Scheduler::new(),
+            triggered: Vec::new(),
+        };
+        performer_state
+            .scheduler
+            .schedule(MusicTime::new(1, 1, 1), "Kick")
+            .schedule(MusicTime::new(2, 2, 5), "Snare")
+            .schedule(MusicTime::new(1, 3, 1), "Hat");
+
+        let mut performer = crate::create_performance_engine(3, 4, 155.0).unwrap();
+        while performer.get_current_time() < &MusicTime::new(2, 2, 1) {
+            performer.tick(Duration::from_millis(10), &mut performer_state);
+        }
+        assert_eq!(
+            performer_state.triggered,
+            vec![
+                (MusicTime::new(1, 1, 1), "Kick"),
+                (MusicTime::new(1, 3, 1), "Hat"),
+            ]
+        );
+
+        while performer.get_current_time() < &MusicTime::new(3, 1, 1) {
+            performer.tick(Duration::from_millis(10), &mut performer_state);
+        }
+        assert_eq!(performer_state.triggered.len(), 3);
+        assert_eq!(
+            performer_state.triggered[2],
+            (MusicTime::new(2, 2, 5), "Snare")
+        );
+        assert!(performer_state.scheduler.is_empty());
+    }
+
+    #[test]
+    fn test_advance_within() {
+        use crate::{music_time::MusicTime, scheduler::Scheduler, time_signature::TimeSignature};
+
+        let time_signature = TimeSignature::new(4, 4);
+        let mut scheduler = Scheduler::new();
+        scheduler.schedule(MusicTime::new(2, 2, 5), "Snare");
+
+        // Exact comparison misses the snare when the positions skip over it
+        let positions = [MusicTime::new(2, 2, 3), MusicTime::new(2, 2, 6)];
+        let exact_hits = positions
+            .iter()
+            .filter(|now| scheduler.next_time() == Some(now))
+            .count();
+        assert_eq!(exact_hits, 0);
+
+        // One beat interval late is caught with a tolerance of 1
+        assert!(scheduler
+            .advance_within(&positions[0], &time_signature, 1)
+            .is_empty());
+        assert_eq!(
+            scheduler.advance_within(&positions[1], &time_signature, 1),
+            vec!["Snare"]
+        );
+        assert!(scheduler.is_empty());
+
+        // Early by the tolerance fires, later than the tolerance is dropped
+        scheduler
+            .schedule(MusicTime::new(1, 4, 6), "Late")
+            .schedule(MusicTime::new(1, 4, 7), "Just late")
+            .schedule(MusicTime::new(2, 1, 3), "Early")
+            .schedule(MusicTime::new(2, 1, 4), "Too early");
+        assert_eq!(
+            scheduler.advance_within(&MusicTime::new(2, 1, 1), &time_signature, 2),
+            vec!["Just late", "Early"]
+        );
+        assert_eq!(scheduler.next_time(), Some(&MusicTime::new(2, 1, 4)));
+    }
+}
@@ -0,0 +1,142 @@
+#![allow(dead_code)]
+
+//!
+//! Data structure that derives beats per minute from tapping.
+//!
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Debug)]
+/// Data structure that derives beats per minute from the time between taps.
+pub struct TapTempo {
+    taps: VecDeque<Instant>,
+    sample_size: usize,
+    timeout: Duration,
+}
+
+impl TapTempo {
+    /// Create a new `TapTempo`.
+    ///
+    /// # Arguments
+    /// * `sample_size` - The number of most recent intervals between taps to average. Must be none 0.
+    /// * `timeout` - Taps further apart than this start a new tempo.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::tap_tempo::TapTempo;
+    /// let tap_tempo = TapTempo::new(4, std::time::Duration::from_secs(2));
+    /// ```
+    pub fn new(sample_size: usize, timeout: Duration) -> Self {
+        TapTempo {
+            taps: VecDeque::with_capacity(sample_size + 1),
+            sample_size,
+            timeout,
+        }
+    }
+
+    /// Tap the tempo. Returns the beats per minute averaged over the most recent taps once
+    /// there are at least two taps.
+    ///
+    /// # Arguments
+    /// * `at` - The instant of the tap.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::tap_tempo::TapTempo;
+    /// use std::time::{Duration, Instant};
+    /// let mut tap_tempo = TapTempo::default();
+    /// let start = Instant::now();
+    /// assert_eq!(tap_tempo.tap(start), None);
+    /// assert_eq!(tap_tempo.tap(start + Duration::from_millis(500)), Some(120.0));
+    /// ```
+    pub fn tap(&mut self, at: Instant) -> Option<f32> {
+        let is_timed_out = match self.taps.back() {
+            Some(previous_tap) => at.saturating_duration_since(*previous_tap) > self.timeout,
+            None => false,
+        };
+        if is_timed_out {
+            self.reset();
+        }
+
+        self.taps.push_back(at);
+        while self.taps.len() > self.sample_size + 1 {
+            self.taps.pop_front();
+        }
+
+        self.get_bpm()
+    }
+
+    /// Gets the beats per minute averaged over the most recent taps, if there are
+    /// at least two taps.
+    pub fn get_bpm(&self) -> Option<f32> {
+        let (first_tap, last_tap) = (self.taps.front()?, self.taps.back()?);
+        let intervals = self.taps.len() as u32 - 1;
+        let span = last_tap.saturating_duration_since(*first_tap);
+        if intervals == 0 || span == Duration::default() {
+            return None;
+        }
+        let seconds_per_beat = span.as_secs_f64() / f64::from(intervals);
+        Some((60.0 / seconds_per_beat) as f32)
+    }
+
+    /// Forget all taps.
+    pub fn reset(&mut self) {
+        self.taps.clear();
+    }
+}
+
+impl Default for TapTempo {
+    /// Default `TapTempo` averages the last 4 intervals with a timeout of 2 seconds.
+    fn default() -> Self {
+        TapTempo::new(4, Duration::from_secs(2))
+    }
+}
+
+mod tests {
+    #[test]
+    fn test_even_taps() {
+        use crate::tap_tempo::TapTempo;
+        use std::time::{Duration, Instant};
+
+        let mut tap_tempo = TapTempo::default();
+        let start = Instant::now();
+        assert_eq!(tap_tempo.tap(start), None);
+        let mut bpm = None;
+        for tap in 1..8 {
+            bpm = tap_tempo.tap(start + Duration::from_millis(tap * 400));
+        }
+        let bpm = bpm.unwrap();
+        assert!((bpm - 150.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_averaged_taps() {
+        use crate::tap_tempo::TapTempo;
+        use std::time::{Duration, Instant};
+
+        let mut tap_tempo = TapTempo::new(2, Duration::from_secs(2));
+        let start = Instant::now();
+        tap_tempo.tap(start);
+        tap_tempo.tap(start + Duration::from_millis(1000));
+        tap_tempo.tap(start + Duration::from_millis(1480));
+        let bpm = tap_tempo.tap(start + Duration::from_millis(2000)).unwrap();
+        assert!((bpm - 120.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_timeout() {
+        use crate::tap_tempo::TapTempo;
+        use std::time::{Duration, Instant};
+
+        let mut tap_tempo = TapTempo::default();
+        let start = Instant::now();
+        tap_tempo.tap(start);
+        assert!(tap_tempo.tap(start + Duration::from_millis(500)).is_some());
+        assert_eq!(tap_tempo.tap(start + Duration::from_secs(5)), None);
+        let bpm = tap_tempo.tap(start + Duration::from_millis(6000)).unwrap();
+        assert!((bpm - 60.0).abs() < 0.01);
+    }
+}
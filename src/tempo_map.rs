@@ -0,0 +1,199 @@
+#![allow(dead_code)]
+
+//!
+//! Data structure that holds changes of bpm and time signature in music time.
+//!
+
+use super::{
+    music_time::MusicTime,
+    music_timer_engine::{validate_bpm, BpmError},
+    time_signature::{TimeSignature, TimeSignatureError},
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// Data structure that holds the change points of bpm and time signature of an
+/// arrangement, sorted by music time. Each change is active from its music time until
+/// the next change. Follow it with `MusicTimerEngine::set_tempo_map`.
+pub struct TempoMap {
+    bpm_changes: Vec<(MusicTime, f32)>,
+    time_signature_changes: Vec<(MusicTime, TimeSignature)>,
+}
+
+impl TempoMap {
+    /// Create a new empty `TempoMap`.
+    ///
+    /// # Example
+    /// ```
+    /// let tempo_map = music_timer::tempo_map::TempoMap::new();
+    /// ```
+    pub fn new() -> Self {
+        TempoMap::default()
+    }
+
+    /// Add a change of bpm, replacing any change of bpm at the same music time. An error
+    /// is returned if the bpm is not finite and greater than zero.
+    ///
+    /// # Arguments
+    /// * `time` - The music time the bpm changes at.
+    /// * `bpm` - The beats per minute from `time`.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{music_time::MusicTime, tempo_map::TempoMap};
+    /// let mut tempo_map = TempoMap::new();
+    /// tempo_map
+    ///     .add_bpm(MusicTime::new(1, 1, 1), 120.0)
+    ///     .unwrap()
+    ///     .add_bpm(MusicTime::new(9, 1, 1), 90.0)
+    ///     .unwrap();
+    /// assert_eq!(tempo_map.bpm_at(&MusicTime::new(8, 4, 8)), Some(120.0));
+    /// assert_eq!(tempo_map.bpm_at(&MusicTime::new(9, 1, 1)), Some(90.0));
+    /// ```
+    pub fn add_bpm(&mut self, time: MusicTime, bpm: f32) -> Result<&mut Self, BpmError> {
+        validate_bpm(bpm)?;
+        insert_change(&mut self.bpm_changes, time, bpm);
+        Ok(self)
+    }
+
+    /// Add a change of time signature, replacing any change of time signature at the same
+    /// music time. The engine applies it from the start of a bar, so `time` should be the
+    /// first beat interval of a bar. An error is returned for an invalid time signature,
+    /// see `TimeSignature::validate`.
+    ///
+    /// # Arguments
+    /// * `time` - The music time the time signature changes at.
+    /// * `time_signature` - The time signature from `time`.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::{music_time::MusicTime, tempo_map::TempoMap, time_signature::TimeSignature};
+    /// let mut tempo_map = TempoMap::new();
+    /// tempo_map
+    ///     .add_time_signature(MusicTime::new(5, 1, 1), TimeSignature::new(7, 8))
+    ///     .unwrap();
+    /// assert_eq!(tempo_map.time_signature_at(&MusicTime::new(4, 1, 1)), None);
+    /// assert_eq!(
+    ///     tempo_map.time_signature_at(&MusicTime::new(5, 1, 1)),
+    ///     Some(TimeSignature::new(7, 8))
+    /// );
+    /// ```
+    pub fn add_time_signature(
+        &mut self,
+        time: MusicTime,
+        time_signature: TimeSignature,
+    ) -> Result<&mut Self, TimeSignatureError> {
+        time_signature.validate()?;
+        insert_change(&mut self.time_signature_changes, time, time_signature);
+        Ok(self)
+    }
+
+    /// Gets the bpm active at a music time, `None` if it is before the first change of bpm.
+    ///
+    /// # Arguments
+    /// * `time` - The music time to look up.
+    pub fn bpm_at(&self, time: &MusicTime) -> Option<f32> {
+        active_change(&self.bpm_changes, time)
+    }
+
+    /// Gets the time signature active at a music time, `None` if it is before the first
+    /// change of time signature.
+    ///
+    /// # Arguments
+    /// * `time` - The music time to look up.
+    pub fn time_signature_at(&self, time: &MusicTime) -> Option<TimeSignature> {
+        active_change(&self.time_signature_changes, time)
+    }
+
+    /// Gets the changes of bpm, sorted by music time.
+    pub fn bpm_changes(&self) -> &[(MusicTime, f32)] {
+        &self.bpm_changes
+    }
+
+    /// Gets the changes of time signature, sorted by music time.
+    pub fn time_signature_changes(&self) -> &[(MusicTime, TimeSignature)] {
+        &self.time_signature_changes
+    }
+
+    /// Returns `true` if there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.bpm_changes.is_empty() && self.time_signature_changes.is_empty()
+    }
+}
+
+/// Insert a change keeping the changes sorted, replacing a change at the same music time.
+fn insert_change<T>(changes: &mut Vec<(MusicTime, T)>, time: MusicTime, value: T) {
+    let index = changes.partition_point(|(change_time, _)| change_time < &time);
+    match changes.get_mut(index) {
+        Some(change) if change.0 == time => change.1 = value,
+        _ => changes.insert(index, (time, value)),
+    }
+}
+
+/// Gets the value of the last change at or before a music time.
+fn active_change<T: Copy>(changes: &[(MusicTime, T)], time: &MusicTime) -> Option<T> {
+    let count = changes.partition_point(|(change_time, _)| change_time <= time);
+    count.checked_sub(1).map(|index| changes[index].1)
+}
+
+mod tests {
+    #[test]
+    fn test_changes() {
+        use crate::{music_time::MusicTime, tempo_map::TempoMap, time_signature::TimeSignature};
+
+        let mut tempo_map = TempoMap::new();
+        assert!(tempo_map.is_empty());
+        tempo_map
+            .add_bpm(MusicTime::new(3, 1, 1), 90.0)
+            .unwrap()
+            .add_bpm(MusicTime::new(1, 1, 1), 120.0)
+            .unwrap()
+            .add_bpm(MusicTime::new(3, 1, 1), 100.0)
+            .unwrap();
+        tempo_map
+            .add_time_signature(MusicTime::new(2, 1, 1), TimeSignature::new(3, 4))
+            .unwrap();
+        assert!(!tempo_map.is_empty());
+
+        // Sorted, with the change at the same music time replaced
+        assert_eq!(
+            tempo_map.bpm_changes(),
+            &[
+                (MusicTime::new(1, 1, 1), 120.0),
+                (MusicTime::new(3, 1, 1), 100.0)
+            ]
+        );
+        assert_eq!(tempo_map.bpm_at(&MusicTime::new(0, 4, 8)), None);
+        assert_eq!(tempo_map.bpm_at(&MusicTime::new(1, 1, 1)), Some(120.0));
+        assert_eq!(tempo_map.bpm_at(&MusicTime::new(2, 4, 8)), Some(120.0));
+        assert_eq!(tempo_map.bpm_at(&MusicTime::new(3, 1, 1)), Some(100.0));
+        assert_eq!(tempo_map.bpm_at(&MusicTime::new(99, 1, 1)), Some(100.0));
+        assert_eq!(tempo_map.time_signature_at(&MusicTime::new(1, 4, 8)), None);
+        assert_eq!(
+            tempo_map.time_signature_at(&MusicTime::new(2, 1, 1)),
+            Some(TimeSignature::new(3, 4))
+        );
+    }
+
+    #[test]
+    fn test_invalid_changes() {
+        use crate::{
+            music_time::MusicTime,
+            music_timer_engine::BpmError,
+            tempo_map::TempoMap,
+            time_signature::{TimeSignature, TimeSignatureError},
+        };
+
+        let mut tempo_map = TempoMap::new();
+        assert_eq!(
+            tempo_map.add_bpm(MusicTime::new(1, 1, 1), 0.0).err(),
+            Some(BpmError::NotPositive)
+        );
+        assert_eq!(
+            tempo_map
+                .add_time_signature(MusicTime::new(1, 1, 1), TimeSignature::new(4, 3))
+                .err(),
+            Some(TimeSignatureError::UnsupportedDenominator)
+        );
+        assert!(tempo_map.is_empty());
+    }
+}
@@ -4,6 +4,39 @@
 //! Data structure of numerator(upper) and denominator(lower) values of music time signature
 //!
 
+use core::{
+    convert::TryFrom,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+/// The number of beat intervals `MusicTime` divides a beat into. A `MusicTimeCounter`
+/// counts in it unless set otherwise, see `MusicTimeCounter::resolution`.
+pub const DEFAULT_INTERVAL_RESOLUTION: u8 = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Errors that can occur when validating a time signature.
+pub enum TimeSignatureError {
+    /// The numerator is 0.
+    ZeroNumerator,
+    /// The denominator is not 2, 4, 8, 16 or 32.
+    UnsupportedDenominator,
+}
+
+impl fmt::Display for TimeSignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimeSignatureError::ZeroNumerator => write!(f, "The numerator cannot be 0"),
+            TimeSignatureError::UnsupportedDenominator => {
+                write!(f, "The denominator must be 2, 4, 8, 16 or 32")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TimeSignatureError {}
+
 #[derive(Clone, Copy, Debug)]
 /// Data structure of numerator(upper) and denominator(lower) values of music time signature
 pub struct TimeSignature {
@@ -33,14 +66,35 @@ impl TimeSignature {
     /// recommend that denominator values should only be 2, 4, 8, 16 or 32. The
     /// numerator cannot be 0. It is your responsibility to create a valid `TimeSignature`.
     pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Returns the reason the time signature is invalid, see `is_valid`.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::time_signature::{TimeSignature, TimeSignatureError};
+    /// assert_eq!(TimeSignature::new(4, 4).validate(), Ok(()));
+    /// assert_eq!(
+    ///     TimeSignature::new(0, 4).validate(),
+    ///     Err(TimeSignatureError::ZeroNumerator)
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), TimeSignatureError> {
         let denominator = self.denominator;
 
-        self.numerator > 0
-            && (denominator == 2
-                || denominator == 4
-                || denominator == 8
-                || denominator == 16
-                || denominator == 32)
+        if self.numerator == 0 {
+            Err(TimeSignatureError::ZeroNumerator)
+        } else if denominator == 2
+            || denominator == 4
+            || denominator == 8
+            || denominator == 16
+            || denominator == 32
+        {
+            Ok(())
+        } else {
+            Err(TimeSignatureError::UnsupportedDenominator)
+        }
     }
 
     /// Get the top value of the time signature.
@@ -53,6 +107,19 @@ impl TimeSignature {
         self.denominator
     }
 
+    /// Returns `true` for a compound meter, where the beats are grouped in threes, _e.g._
+    /// 6/8, 9/8 and 12/8.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::time_signature::TimeSignature;
+    /// assert!(TimeSignature::new(6, 8).is_compound());
+    /// assert!(!TimeSignature::new(3, 4).is_compound());
+    /// ```
+    pub fn is_compound(&self) -> bool {
+        self.numerator > 3 && self.numerator.is_multiple_of(3)
+    }
+
     // Return the numerator and denominator as a tuple.
     pub fn as_tuple(&self) -> (u8, u8) {
         (self.numerator, self.denominator)
@@ -65,6 +132,34 @@ impl PartialEq for TimeSignature {
     }
 }
 
+impl Eq for TimeSignature {}
+
+impl Hash for TimeSignature {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.numerator.hash(state);
+        self.denominator.hash(state);
+    }
+}
+
+impl TryFrom<(u8, u8)> for TimeSignature {
+    type Error = TimeSignatureError;
+
+    /// Create a valid time signature from a tuple of the numerator and denominator, see
+    /// `TimeSignature::validate`.
+    ///
+    /// # Example
+    /// ```
+    /// use music_timer::time_signature::TimeSignature;
+    /// use std::convert::TryFrom;
+    /// assert_eq!(TimeSignature::try_from((7, 8)), Ok(TimeSignature::new(7, 8)));
+    /// ```
+    fn try_from((numerator, denominator): (u8, u8)) -> Result<Self, Self::Error> {
+        let time_signature = TimeSignature::new(numerator, denominator);
+        time_signature.validate()?;
+        Ok(time_signature)
+    }
+}
+
 impl Default for TimeSignature {
     // Default is `TimeSignature::new(4,4)`.
     fn default() -> TimeSignature {
@@ -75,6 +170,7 @@ impl Default for TimeSignature {
     }
 }
 
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     #[test]
     fn test_valid() {
@@ -84,6 +180,20 @@ mod tests {
         assert_eq!(TimeSignature::new(0, 2).is_valid(), false);
     }
 
+    #[test]
+    fn test_validate() {
+        use crate::time_signature::{TimeSignature, TimeSignatureError};
+        assert_eq!(TimeSignature::new(7, 8).validate(), Ok(()));
+        assert_eq!(
+            TimeSignature::new(0, 4).validate(),
+            Err(TimeSignatureError::ZeroNumerator)
+        );
+        assert_eq!(
+            TimeSignature::new(4, 3).validate(),
+            Err(TimeSignatureError::UnsupportedDenominator)
+        );
+    }
+
     #[test]
     fn test_equality() {
         use crate::time_signature::TimeSignature;
@@ -95,4 +205,51 @@ mod tests {
         let b = TimeSignature::new(4, 4);
         assert_ne!(a, b);
     }
+
+    #[test]
+    fn test_hash() {
+        use crate::time_signature::TimeSignature;
+        use std::collections::HashSet;
+
+        let mut time_signatures = HashSet::new();
+        time_signatures.insert(TimeSignature::new(4, 4));
+        time_signatures.insert(TimeSignature::default());
+        assert_eq!(time_signatures.len(), 1);
+        time_signatures.insert(TimeSignature::new(3, 4));
+        assert_eq!(time_signatures.len(), 2);
+    }
+
+    #[test]
+    fn test_try_from() {
+        use crate::time_signature::{TimeSignature, TimeSignatureError};
+        use std::convert::TryFrom;
+
+        assert_eq!(
+            TimeSignature::try_from((7, 8)),
+            Ok(TimeSignature::new(7, 8))
+        );
+        assert_eq!(
+            TimeSignature::try_from((4, 4)),
+            Ok(TimeSignature::default())
+        );
+        assert_eq!(
+            TimeSignature::try_from((0, 4)),
+            Err(TimeSignatureError::ZeroNumerator)
+        );
+        assert_eq!(
+            TimeSignature::try_from((4, 3)),
+            Err(TimeSignatureError::UnsupportedDenominator)
+        );
+    }
+
+    #[test]
+    fn test_is_compound() {
+        use crate::time_signature::TimeSignature;
+        assert!(TimeSignature::new(6, 8).is_compound());
+        assert!(TimeSignature::new(9, 8).is_compound());
+        assert!(TimeSignature::new(12, 16).is_compound());
+        assert!(!TimeSignature::new(3, 8).is_compound());
+        assert!(!TimeSignature::new(4, 4).is_compound());
+        assert!(!TimeSignature::new(7, 8).is_compound());
+    }
 }
@@ -1,10 +1,16 @@
-use music_timer::{music_time::MusicTime, music_timer_engine::MusicTimerState};
+#![allow(clippy::clone_on_copy)]
+
+use music_timer::{
+    music_time::MusicTime, music_timer_engine::MusicTimerState, time_signature::TimeSignatureError,
+};
 
 struct PerformanceState {
     current_time: MusicTime,
     count_bars: u8,
     count_beats: u8,
     count_beat_intervals: u8,
+    bars: Vec<u16>,
+    beat_intervals: Vec<MusicTime>,
 }
 
 impl PerformanceState {
@@ -14,6 +20,8 @@ impl PerformanceState {
             count_bars: 0,
             count_beats: 0,
             count_beat_intervals: 0,
+            bars: Vec::new(),
+            beat_intervals: Vec::new(),
         }
     }
 }
@@ -22,6 +30,7 @@ impl MusicTimerState for PerformanceState {
     fn on_beat_interval(&mut self, now_time: &MusicTime) {
         self.current_time = now_time.clone();
         self.count_beat_intervals += 1;
+        self.beat_intervals.push(*now_time);
     }
     fn on_beat(&mut self, now_time: &MusicTime) {
         self.current_time = now_time.clone();
@@ -30,6 +39,7 @@ impl MusicTimerState for PerformanceState {
     fn on_bar(&mut self, now_time: &MusicTime) {
         self.current_time = now_time.clone();
         self.count_bars += 1;
+        self.bars.push(now_time.get_bar());
     }
 }
 
@@ -41,7 +51,7 @@ fn performance_runner(
     use std::thread;
 
     let mut performer =
-        music_timer::create_performance_engine(time_bpm.0, time_bpm.1, time_bpm.2 as f32);
+        music_timer::create_performance_engine(time_bpm.0, time_bpm.1, time_bpm.2 as f32).unwrap();
     let sleep_duration = performer.get_beat_interval_duration() / 2;
     while performer_state.current_time < end_time {
         performer.pulse(performer_state);
@@ -58,7 +68,7 @@ fn test_performance_example() {
     assert_eq!(performer_state.current_time, end_time);
     assert_eq!(performer_state.count_beat_intervals, 8 * 3 * 4);
     assert_eq!(performer_state.count_beats, 3 * 4);
-    assert_eq!(performer_state.count_bars, 4);
+    assert_eq!(performer_state.count_bars, 3);
 }
 #[test]
 fn test_standard() {
@@ -69,7 +79,7 @@ fn test_standard() {
     assert_eq!(performer_state.current_time, end_time);
     assert_eq!(performer_state.count_beat_intervals, 8 * 4 * 4);
     assert_eq!(performer_state.count_beats, 4 * 4);
-    assert_eq!(performer_state.count_bars, 4);
+    assert_eq!(performer_state.count_bars, 3);
 }
 #[test]
 fn test_odd() {
@@ -80,5 +90,122 @@ fn test_odd() {
     assert_eq!(performer_state.current_time, end_time);
     assert_eq!(performer_state.count_beat_intervals, 8 * 7 * 4);
     assert_eq!(performer_state.count_beats, 7 * 4);
-    assert_eq!(performer_state.count_bars, 4);
+    assert_eq!(performer_state.count_bars, 3);
+}
+
+#[test]
+fn test_stop() {
+    use std::thread;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = music_timer::create_performance_engine(4, 4, 240.0).unwrap();
+    let sleep_duration = performer.get_beat_interval_duration() / 2;
+    let end_time = MusicTime::new(1, 2, 1);
+    while performer_state.current_time < end_time {
+        performer.pulse(&mut performer_state);
+        thread::sleep(sleep_duration);
+    }
+    assert_eq!(performer_state.current_time, end_time);
+
+    performer.stop();
+    assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 1));
+
+    let mut performer_state = PerformanceState::new();
+    performer.play();
+    performer.pulse(&mut performer_state);
+    assert_eq!(performer_state.current_time, MusicTime::new(1, 1, 1));
+    assert_eq!(performer_state.count_beat_intervals, 1);
+    assert_eq!(performer_state.count_beats, 1);
+    assert_eq!(performer_state.count_bars, 0);
+
+    while performer_state.current_time < end_time {
+        performer.pulse(&mut performer_state);
+        thread::sleep(sleep_duration);
+    }
+    assert_eq!(performer_state.current_time, end_time);
+    assert_eq!(performer_state.count_beat_intervals, 9);
+}
+
+#[test]
+fn test_set_bpm() {
+    use std::{thread, time::SystemTime};
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+    let duration_120 = performer.get_beat_interval_duration();
+    performer.set_bpm(240.0);
+    let duration_240 = performer.get_beat_interval_duration();
+    assert_eq!(duration_240, duration_120 / 2);
+
+    let start_time = SystemTime::now();
+    let end_time = MusicTime::new(1, 2, 1);
+    while performer_state.current_time < end_time {
+        performer.pulse(&mut performer_state);
+        thread::sleep(duration_240 / 4);
+    }
+    let elapsed = start_time.elapsed().unwrap();
+
+    assert_eq!(performer_state.count_beat_intervals, 9);
+    assert!(elapsed >= duration_240 * 8, "Time was too fast");
+    assert!(elapsed < duration_120 * 6, "Time is too slow");
+}
+
+#[test]
+fn test_get_bpm() {
+    let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+    assert_eq!(performer.get_bpm(), 120.0);
+    performer.set_bpm(97.5);
+    assert_eq!(performer.get_bpm(), 97.5);
+}
+
+#[test]
+fn test_on_bar_once_per_bar() {
+    let mut performer_state = PerformanceState::new();
+    let end_time = MusicTime::new(4, 1, 1);
+    performance_runner((2, 4, 240), &mut performer_state, end_time);
+
+    assert_eq!(performer_state.current_time, end_time);
+    assert_eq!(performer_state.count_bars, 3);
+    assert_eq!(performer_state.bars, vec![2, 3, 4]);
+}
+
+#[test]
+fn test_pulse_catch_up() {
+    use std::thread;
+
+    let mut performer_state = PerformanceState::new();
+    let mut performer = music_timer::create_performance_engine(4, 4, 120.0).unwrap();
+    let interval_duration = performer.get_beat_interval_duration();
+    performer.pulse(&mut performer_state);
+    assert_eq!(performer_state.count_beat_intervals, 1);
+
+    thread::sleep(interval_duration * 5 + interval_duration / 2);
+    performer.pulse(&mut performer_state);
+
+    assert_eq!(performer_state.count_beat_intervals, 6);
+    assert_eq!(
+        performer_state.beat_intervals,
+        vec![
+            MusicTime::new(1, 1, 1),
+            MusicTime::new(1, 1, 2),
+            MusicTime::new(1, 1, 3),
+            MusicTime::new(1, 1, 4),
+            MusicTime::new(1, 1, 5),
+            MusicTime::new(1, 1, 6),
+        ]
+    );
+    assert_eq!(performer.get_current_time(), &MusicTime::new(1, 1, 7));
+}
+
+#[test]
+fn test_invalid_time_signature() {
+    assert_eq!(
+        music_timer::create_performance_engine(0, 4, 120.0).err(),
+        Some(TimeSignatureError::ZeroNumerator)
+    );
+    assert_eq!(
+        music_timer::create_performance_engine(4, 3, 120.0).err(),
+        Some(TimeSignatureError::UnsupportedDenominator)
+    );
+    assert!(music_timer::create_performance_engine(7, 8, 120.0).is_ok());
 }
@@ -46,7 +46,7 @@ impl MusicTimerState for PerformanceState {
 fn test_drift_60bpm() {
     use std::thread;
 
-    let mut performer = music_timer::create_performance_engine(4, 4, 60.0);
+    let mut performer = music_timer::create_performance_engine(4, 4, 60.0).unwrap();
     let mut performer_state = PerformanceState::new(performer.get_beat_interval_duration());
     let end_time = MusicTime::new(3, 1, 1);
 
@@ -73,7 +73,7 @@ fn test_drift_60bpm() {
 fn test_drift_140bpm() {
     use std::thread;
 
-    let mut performer = music_timer::create_performance_engine(3, 4, 140.0);
+    let mut performer = music_timer::create_performance_engine(3, 4, 140.0).unwrap();
     let mut performer_state = PerformanceState::new(performer.get_beat_interval_duration());
     let end_time = MusicTime::new(3, 1, 1);
 